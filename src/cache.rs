@@ -1,12 +1,21 @@
+#[cfg(feature = "serde")]
+mod persist;
+mod shared;
+
+pub use shared::SharedIconsCache;
+
 use crate::theme::DirectoryRef;
-use crate::{IconFile, Icons, Theme};
+use crate::{Context, DirectoryInfo, IconFile, IconMatch, Icons, LookupOptions, Theme};
 use qp_trie::wrapper::BString;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::{OsStr, OsString};
 use std::sync::Arc;
 
 /// Caching version of [`Icons`].
 ///
+/// With the `serde` feature enabled, the cache can be saved to disk using
+/// [`save_cache`](IconsCache::save_cache) and restored later using [`load_cache`](IconsCache::load_cache).
+///
 /// # Example
 ///
 /// ```
@@ -24,6 +33,8 @@ pub struct IconsCache {
     /// also appear in this map. For the same reason, both `icons` and `themes` aren't `pub`;
     /// otherwise users could break that invariant.
     themes: HashMap<OsString, ThemeCache>,
+    // The capacity of each theme's cache, if limited.
+    max_entries: Option<usize>,
 }
 
 impl IconsCache {
@@ -32,6 +43,24 @@ impl IconsCache {
         icons.into()
     }
 
+    /// Creates a new [`IconsCache`] from [`Icons`], where the cache of each theme holds at most
+    /// `max_entries` icon names.
+    ///
+    /// See [`ThemeCache::with_capacity`].
+    pub fn with_capacity(icons: Icons, max_entries: usize) -> Self {
+        let themes = icons
+            .themes
+            .iter()
+            .map(|(k, v)| (k.clone(), ThemeCache::with_capacity(v.clone(), max_entries)))
+            .collect();
+
+        Self {
+            icons,
+            themes,
+            max_entries: Some(max_entries),
+        }
+    }
+
     /// Like [`find_icon`](self.find_icon), with `theme` being `"hicolor"`, which is the default icon theme.
     ///
     /// Caching version of [`Icons::find_default_icon`]
@@ -55,6 +84,96 @@ impl IconsCache {
         scale: u32,
         theme: &str,
     ) -> Option<IconFile> {
+        self.find_icon_with_options(icon_name, size, scale, theme, &LookupOptions::default())
+    }
+
+    /// Look up many icons of the same size, scale and theme at once.
+    ///
+    /// The returned list holds the result for each name in `names`, in the same order.
+    /// This gives the same results as calling [`find_icon`](IconsCache::find_icon) for each name,
+    /// but only resolves the theme once.
+    pub fn find_icons(
+        &mut self,
+        names: &[&str],
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Vec<Option<IconFile>> {
+        let theme = match self.themes.get_mut(OsStr::new(theme)) {
+            Some(theme) => theme,
+            None => match self.themes.get_mut(OsStr::new("hicolor")) {
+                Some(theme) => theme,
+                None => return vec![None; names.len()],
+            },
+        };
+
+        names
+            .iter()
+            .map(|icon_name| {
+                if icon_name.is_empty() {
+                    return None;
+                }
+
+                theme
+                    .find_icon(icon_name, size, scale)
+                    .or_else(|| self.icons.find_standalone_icon(icon_name))
+            })
+            .collect()
+    }
+
+    /// Look up an icon by name, size, scale and theme, only considering icons of a certain [`Context`].
+    ///
+    /// Caching version of [`Icons::find_icon_in_context`].
+    pub fn find_icon_in_context(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+        context: Context,
+    ) -> Option<IconFile> {
+        let options = LookupOptions {
+            context: Some(context),
+            ..Default::default()
+        };
+
+        self.find_icon_with_options(icon_name, size, scale, theme, &options)
+    }
+
+    /// Look up the first icon found of a list of names, by size, scale and theme.
+    ///
+    /// Caching version of [`Icons::find_icon_first_of`].
+    pub fn find_icon_first_of(
+        &mut self,
+        names: &[&str],
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconFile> {
+        let theme = match self.theme_cache_mut(theme) {
+            Some(theme) => theme,
+            None => self.theme_cache_mut("hicolor")?,
+        };
+
+        theme.find_icon_first_of(names, size, scale).or_else(|| {
+            names
+                .iter()
+                .filter(|icon_name| !icon_name.is_empty())
+                .find_map(|icon_name| self.find_standalone_icon(icon_name))
+        })
+    }
+
+    /// Look up an icon by name, size, scale and theme, along with details on the directory it was
+    /// found in.
+    ///
+    /// Caching version of [`Icons::find_icon_detailed`].
+    pub fn find_icon_detailed(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconMatch> {
         if icon_name.is_empty() {
             return None;
         }
@@ -64,9 +183,63 @@ impl IconsCache {
             None => self.theme_cache_mut("hicolor")?,
         };
 
-        theme
-            .find_icon(icon_name, size, scale)
-            .or_else(|| self.find_standalone_icon(icon_name))
+        theme.find_icon_detailed(icon_name, size, scale)
+    }
+
+    /// Look up the icon for a MIME type, like `text/html`, by size, scale and theme.
+    ///
+    /// Caching version of [`Icons::find_mime_icon`].
+    pub fn find_mime_icon(
+        &mut self,
+        mime: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconFile> {
+        let names = Icons::mime_icon_names(mime)?;
+
+        self.find_icon_first_of(&names.each_ref().map(String::as_str), size, scale, theme)
+    }
+
+    /// Look up an icon by name, size, scale and theme, with additional [`LookupOptions`].
+    ///
+    /// Caching version of [`Icons::find_icon_with_options`].
+    pub fn find_icon_with_options(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        if icon_name.is_empty() {
+            return None;
+        }
+
+        let theme = self
+            .icons
+            .theme(theme)
+            .or_else(|| self.icons.theme("hicolor"))?;
+        // borrow the theme caches only, as the standalone icons are consulted in between.
+        let themes = &mut self.themes;
+        let icons = &self.icons;
+
+        themes
+            .get_mut(&theme.info.internal_name)?
+            .find_icon_matching_case(icon_name, size, scale, options)
+            .or_else(|| icons.find_standalone_icon_matching_case(icon_name, options))
+            .or_else(|| {
+                // unlike the names matching in case, the parents use their own caches for this,
+                // so that their directories are only read once.
+                std::iter::once(&theme)
+                    .chain(theme.inherits_from.iter())
+                    .find_map(|theme| {
+                        themes
+                            .get_mut(&theme.info.internal_name)?
+                            .find_icon_here_ignoring_case(icon_name, size, scale, options)
+                    })
+            })
+            .or_else(|| icons.find_standalone_icon_ignoring_case(icon_name, options))
     }
 
     /// Using [`Icons::find_all_icons`], populate the cache with all icons available.
@@ -84,6 +257,11 @@ impl IconsCache {
                 continue;
             };
 
+            if theme.populated {
+                // already fully populated: we'd only end up with duplicate entries.
+                continue;
+            }
+
             let dir_ref = theme.theme.info.index.directories.iter()
                 .position(|d| std::ptr::eq(d, dir));
 
@@ -93,10 +271,83 @@ impl IconsCache {
                 continue;
             };
 
+            theme.touch(icon.icon_name());
             theme.cache.entry(icon.icon_name().into())
                 .or_insert_with(Default::default)
                 .push((dir_ref, icon));
         }
+
+        for theme in self.themes.values_mut() {
+            theme.populated = true;
+            // if the cache can't hold all icons, it's no longer fully populated after this.
+            theme.evict();
+        }
+    }
+
+    /// List the names of all icons starting with `prefix` in a theme and its parents,
+    /// utilizing and populating the internal caches where possible.
+    ///
+    /// Caching version of [`Icons::icon_names_with_prefix`].
+    pub fn icon_names_with_prefix(&mut self, prefix: &str, theme: &str) -> Vec<String> {
+        let Some(theme) = self
+            .icons
+            .theme(theme)
+            .or_else(|| self.icons.theme("hicolor"))
+        else {
+            return Vec::new();
+        };
+
+        let themes = std::iter::once(&theme).chain(theme.inherits_from.iter());
+
+        let mut names = BTreeSet::new();
+        for theme in themes {
+            let Some(theme) = self.themes.get_mut(theme.info.internal_name.as_os_str()) else {
+                continue;
+            };
+
+            names.extend(theme.icon_names_with_prefix(prefix));
+        }
+
+        names.into_iter().collect()
+    }
+
+    /// Rebuild the underlying [`Icons`] by searching its [search paths](Icons::search_paths) again,
+    /// typically after [`Icons::is_stale`] returned `true`.
+    ///
+    /// The caches of themes whose [`ThemeInfo`](crate::ThemeInfo) didn't change are kept, while
+    /// the caches of changed themes start out empty, and those of removed themes are dropped.
+    /// Caches with a capacity keep that capacity.
+    ///
+    /// If the `Icons` have no search paths, there is nothing to search, and they're left as is.
+    pub fn reload(&mut self) {
+        if self.icons.search_paths.is_empty() {
+            return;
+        }
+
+        let icons = Icons::from_search_paths(self.icons.search_paths.clone());
+
+        let mut old_themes = std::mem::take(&mut self.themes);
+        self.themes = icons
+            .themes
+            .iter()
+            .map(|(name, theme)| {
+                let theme_cache = match old_themes.remove(name) {
+                    // the parents of this theme might have changed, so the theme itself is still
+                    // replaced: the cache only holds files of this theme.
+                    Some(mut theme_cache) if theme_cache.theme.info == theme.info => {
+                        theme_cache.theme = theme.clone();
+                        theme_cache
+                    }
+                    _ => match self.max_entries {
+                        Some(max_entries) => ThemeCache::with_capacity(theme.clone(), max_entries),
+                        None => theme.clone().into(),
+                    },
+                };
+
+                (name.clone(), theme_cache)
+            })
+            .collect();
+        self.icons = icons;
     }
 
     /// Access a known icon theme cache by name.
@@ -134,7 +385,11 @@ impl From<Icons> for IconsCache {
             .map(|(k, v)| (k.clone(), v.clone().into()))
             .collect();
 
-        Self { icons, themes }
+        Self {
+            icons,
+            themes,
+            max_entries: None,
+        }
     }
 }
 
@@ -143,6 +398,13 @@ pub struct ThemeCache {
     theme: Arc<Theme>,
     // Cache of icon names to a list of files and the ref (index) of the directory they're in.
     cache: qp_trie::Trie<BString, Vec<(DirectoryRef, IconFile)>>,
+    // Whether all icons of this theme are known to be in the cache.
+    populated: bool,
+    // Only present if the cache has a maximum capacity.
+    recency: Option<Recency>,
+    // Lowercase names of all icons in this theme, mapped to their actual names.
+    // Only built once a case-insensitive lookup needs it.
+    names_by_lowercase: Option<HashMap<String, String>>,
 }
 
 impl ThemeCache {
@@ -151,18 +413,121 @@ impl ThemeCache {
         theme.into()
     }
 
+    /// Create a new [`ThemeCache`] from a given [`Theme`], that holds at most `max_entries`
+    /// icon names.
+    ///
+    /// Once the cache grows beyond that, the least recently used icon names are evicted.
+    /// Icons that weren't found are also cached, so they count towards the limit as well.
+    pub fn with_capacity(theme: Arc<Theme>, max_entries: usize) -> Self {
+        Self {
+            recency: Some(Recency::new(max_entries)),
+            ..theme.into()
+        }
+    }
+
     /// Find an icon in this theme or any of its dependencies, utilizing and populating the internal
     /// cache where possible.
     ///
     /// Analogous to [Theme::find_icon].
     pub fn find_icon(&mut self, icon_name: &str, size: u32, scale: u32) -> Option<IconFile> {
-        self.find_icon_here(icon_name, size, scale).or_else(|| {
-            // or find it in one of our parents
-            self.theme
-                .inherits_from
-                .iter()
-                .find_map(|theme| theme.find_icon_here(icon_name, size, scale))
-        })
+        self.find_icon_with_options(icon_name, size, scale, &LookupOptions::default())
+    }
+
+    /// Find the first icon of a list of names in this theme or any of its dependencies, utilizing
+    /// and populating the internal cache where possible.
+    ///
+    /// Analogous to [Theme::find_icon_first_of].
+    pub fn find_icon_first_of(
+        &mut self,
+        names: &[&str],
+        size: u32,
+        scale: u32,
+    ) -> Option<IconFile> {
+        names
+            .iter()
+            .filter(|icon_name| !icon_name.is_empty())
+            .find_map(|icon_name| self.find_icon(icon_name, size, scale))
+    }
+
+    /// Find an icon in this theme or any of its dependencies, with additional [`LookupOptions`],
+    /// utilizing and populating the internal cache where possible.
+    ///
+    /// Analogous to [Theme::find_icon_with_options].
+    pub fn find_icon_with_options(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        self.find_icon_matching_case(icon_name, size, scale, options)
+            .or_else(|| self.find_icon_ignoring_case(icon_name, size, scale, options))
+    }
+
+    // Like `find_icon_with_options`, but without trying names that differ in case.
+    pub(crate) fn find_icon_matching_case(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        options
+            .icon_names(icon_name)
+            .find_map(|icon_name| self.find_icon_here_in(&icon_name, size, scale, options))
+            .or_else(|| {
+                // or find it in one of our parents
+                self.theme.inherits_from.iter().find_map(|theme| {
+                    theme
+                        .find_icon_here_matching_case(icon_name, size, scale, options)
+                        .map(|(_, icon)| icon)
+                })
+            })
+    }
+
+    // Like `find_icon_with_options`, but only trying names that differ in case, if `options`
+    // allows it.
+    fn find_icon_ignoring_case(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        self.find_icon_here_ignoring_case(icon_name, size, scale, options)
+            .or_else(|| {
+                // like the names matching in case, parents don't use the cache.
+                self.theme.inherits_from.iter().find_map(|theme| {
+                    theme
+                        .find_icon_here_ignoring_case(icon_name, size, scale, options)
+                        .map(|(_, icon)| icon)
+                })
+            })
+    }
+
+    /// Find an icon in this theme or any of its dependencies, along with details on the directory
+    /// it was found in, utilizing and populating the internal cache where possible.
+    ///
+    /// Analogous to [Theme::find_icon_detailed].
+    pub fn find_icon_detailed(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+    ) -> Option<IconMatch> {
+        let options = LookupOptions::default();
+
+        if let Some((directory, icon)) =
+            self.find_icon_ref_here_in(icon_name, size, scale, &options)
+        {
+            return Some(IconMatch::new(&self.theme, directory, icon, size, scale));
+        }
+
+        // like `find_icon`, parents don't use the cache.
+        self.theme
+            .inherits_from
+            .iter()
+            .find_map(|theme| theme.find_icon_detailed_here(icon_name, size, scale, &options))
     }
 
     /// Find an icon in this theme only, utilizing and populating the internal cache where possible.
@@ -171,6 +536,30 @@ impl ThemeCache {
     // for people editing this function: make sure to check, and keep in sync, the behaviour of
     // Theme::find_icon_here with this function.
     pub fn find_icon_here(&mut self, icon_name: &str, size: u32, scale: u32) -> Option<IconFile> {
+        self.find_icon_here_in(icon_name, size, scale, &LookupOptions::default())
+    }
+
+    // Like `find_icon_here`, but only considers the directories allowed by `options`.
+    // Any name fallbacks in `options` are ignored.
+    pub(crate) fn find_icon_here_in(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        self.find_icon_ref_here_in(icon_name, size, scale, options)
+            .map(|(_, icon)| icon)
+    }
+
+    // Like `find_icon_here_in`, but also returns the directory the icon was found in.
+    fn find_icon_ref_here_in(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<(DirectoryRef, IconFile)> {
         // If `icon_name` isn't in the cache yet,
         // let's start by finding all(!) of its files; this is more expensive than the normal
         // lookup function, but we pay the cost upfront to make subsequent lookups quicker!
@@ -181,29 +570,200 @@ impl ThemeCache {
             // if this icon isn't in the cache already, find its files and insert those:
             .or_insert_with(|| self.theme.find_icon_files(icon_name).collect());
 
-        // find an exact match:
+        let icon = Self::best_match(&self.theme, icon_files, size, scale, options);
+
+        self.touch(icon_name);
+        self.evict();
+
+        icon
+    }
+
+    // Like `find_icon_here_in`, but for the names that differ in case from `icon_name` (and its
+    // fallbacks), if `options` allows it.
+    pub(crate) fn find_icon_here_ignoring_case(
+        &mut self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        if !options.case_insensitive {
+            return None;
+        }
+
+        options.icon_names(icon_name).find_map(|icon_name| {
+            let icon_name = self
+                .names_by_lowercase
+                .get_or_insert_with(|| self.theme.icon_names_by_lowercase())
+                .get(&icon_name.to_lowercase())?
+                .clone();
+
+            self.find_icon_here_in(&icon_name, size, scale, options)
+        })
+    }
+
+    /// List all sizes and scales an icon is available at in this theme or any of its dependencies,
+    /// utilizing and populating the internal cache where possible.
+    ///
+    /// Analogous to [Theme::icon_available_sizes].
+    pub fn icon_available_sizes(&mut self, icon_name: &str) -> Vec<(DirectoryInfo, IconFile)> {
+        let icon_files = self
+            .cache
+            .entry(icon_name.into())
+            .or_insert_with(|| self.theme.find_icon_files(icon_name).collect())
+            .clone();
+
+        self.touch(icon_name);
+        self.evict();
+
+        let files = icon_files
+            .into_iter()
+            .map(|(dir, icon)| (self.theme.as_ref(), dir, icon))
+            .chain(self.theme.inherits_from.iter().flat_map(|theme| {
+                theme
+                    .find_icon_files(icon_name)
+                    .map(move |(dir, icon)| (theme.as_ref(), dir, icon))
+            }));
+
+        Theme::available_sizes(files)
+    }
+
+    /// Like `find_icon_here_in`, but only consults the cache.
+    ///
+    /// Returns `None` if `icon_name` isn't in the cache yet, or if this cache has a capacity:
+    /// then, lookups always need mutable access to keep track of which entries were used.
+    pub(crate) fn find_icon_here_cached(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<Option<IconFile>> {
+        if self.recency.is_some() {
+            return None;
+        }
+
+        let icon_files = self.cache.get_str(icon_name)?;
+
+        Some(Self::best_match(&self.theme, icon_files, size, scale, options).map(|(_, icon)| icon))
+    }
+
+    fn best_match(
+        theme: &Theme,
+        icon_files: &[(DirectoryRef, IconFile)],
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<(DirectoryRef, IconFile)> {
+        let icon_files = icon_files
+            .iter()
+            .filter(|(dir, _)| options.allows_directory(&theme.info.index.directories[*dir]));
+
+        // find an exact match, taking the first preferred one if there is any:
+        let exact_match = icon_files
+            .clone()
+            .filter(|(dir, _)| theme.info.index.directories[*dir].matches_size(size, scale))
+            .min_by_key(|(dir, _)| !options.prefers_directory(&theme.info.index.directories[*dir]));
+
+        if let Some((dir, ico)) = exact_match {
+            return Some((*dir, ico.clone()));
+        }
+
+        // else, find the closest match.
+        // like `min_by_key`, the first of equally close matches is kept, but there's no need to look
+        // any further once a match without any distance is found.
+        let mut closest: Option<(u32, DirectoryRef, &IconFile)> = None;
         for (dir, ico) in icon_files {
-            let dir = &self.theme.info.index.directories[*dir];
+            let directory = &theme.info.index.directories[*dir];
+            let distance = options.size_distance(directory, size, scale);
 
-            if dir.matches_size(size, scale) {
-                return Some(ico.clone());
+            if closest.is_none_or(|(min_distance, _, _)| distance < min_distance) {
+                closest = Some((distance, *dir, ico));
+
+                if distance == 0 {
+                    break;
+                }
             }
         }
 
-        // else, find the closest match:
-        // TODO(performance): can early return when min-distance == 0
-        let icon = icon_files.iter().min_by_key(|(dir, _)| {
-            let dir = &self.theme.info.index.directories[*dir];
+        closest.map(|(_, dir, ico)| (dir, ico.clone()))
+    }
+
+    /// List the names of all icons in this theme only starting with `prefix`, utilizing and
+    /// populating the internal cache where possible.
+    ///
+    /// Names are returned in lexicographic order.
+    /// If the cache hasn't been [populated](IconsCache::pre_populate_cache) for this theme yet,
+    /// the theme's directories are read instead, and all icons found are added to the cache.
+    ///
+    /// This function is analogous to [`Theme::icon_names_with_prefix_here`].
+    /// To include the icons of parent themes, use [`IconsCache::icon_names_with_prefix`].
+    pub fn icon_names_with_prefix(&mut self, prefix: &str) -> Vec<String> {
+        if !self.populated {
+            let mut found = BTreeMap::<String, Vec<_>>::new();
+            for (dir, icon) in self.theme.icon_files_with_prefix(prefix) {
+                found
+                    .entry(icon.icon_name().to_owned())
+                    .or_default()
+                    .push((dir, icon));
+            }
 
-            dir.size_distance(size, scale)
-        });
+            let names = found.keys().cloned().collect();
 
-        icon.map(|(_, ico)| ico.clone())
+            for (icon_name, files) in found {
+                self.touch(&icon_name);
+                // it's possible that this icon was cached as "missing" before, so always overwrite
+                self.cache.insert(icon_name.into(), files);
+            }
+
+            // if the prefix is empty, we've just seen every icon there is.
+            self.populated = prefix.is_empty();
+            // with a capacity, some of the names found may not survive this, so they're returned
+            // from the scan above rather than from the cache.
+            self.evict();
+
+            return names;
+        }
+
+        self.cache
+            .iter_prefix_str(prefix)
+            .filter(|(_, files)| !files.is_empty())
+            .map(|(icon_name, _)| icon_name.as_str().to_owned())
+            .collect()
     }
 
     /// Empties the internal cache.
     pub fn clear_cache(&mut self) {
         self.cache.clear();
+        self.populated = false;
+        self.names_by_lowercase = None;
+
+        if let Some(recency) = &mut self.recency {
+            recency.clear();
+        }
+    }
+
+    /// Mark `icon_name` as the most recently used entry, if this cache has a capacity.
+    fn touch(&mut self, icon_name: &str) {
+        if let Some(recency) = &mut self.recency {
+            recency.touch(icon_name);
+        }
+    }
+
+    /// Evict the least recently used entries until the cache is within its capacity.
+    fn evict(&mut self) {
+        let Some(recency) = &mut self.recency else {
+            return;
+        };
+
+        while self.cache.count() > recency.capacity {
+            let Some(icon_name) = recency.pop_least_recently_used() else {
+                break;
+            };
+
+            self.cache.remove_str(icon_name.as_str());
+            self.populated = false;
+        }
     }
 }
 
@@ -212,15 +772,76 @@ impl From<Arc<Theme>> for ThemeCache {
         Self {
             theme,
             cache: Default::default(),
+            populated: false,
+            recency: None,
+            names_by_lowercase: None,
         }
     }
 }
 
+/// Keeps track of the order in which the entries of a [`ThemeCache`] were used.
+struct Recency {
+    capacity: usize,
+    // Incremented for every use, so that entries with a lower tick were used less recently.
+    tick: u64,
+    last_used: HashMap<String, u64>,
+    by_last_use: BTreeMap<u64, String>,
+}
+
+impl Recency {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            last_used: Default::default(),
+            by_last_use: Default::default(),
+        }
+    }
+
+    fn touch(&mut self, icon_name: &str) {
+        self.tick += 1;
+
+        match self.last_used.get_mut(icon_name) {
+            // this is the hot path of cache hits, so the name already stored is moved instead.
+            Some(last_used) => {
+                let icon_name = self
+                    .by_last_use
+                    .remove(last_used)
+                    .expect("kept in sync with `last_used`");
+                *last_used = self.tick;
+                self.by_last_use.insert(self.tick, icon_name);
+            }
+            None => {
+                self.last_used.insert(icon_name.to_owned(), self.tick);
+                self.by_last_use.insert(self.tick, icon_name.to_owned());
+            }
+        }
+    }
+
+    fn pop_least_recently_used(&mut self) -> Option<String> {
+        let (_, icon_name) = self.by_last_use.pop_first()?;
+        self.last_used.remove(&icon_name);
+
+        Some(icon_name)
+    }
+
+    fn clear(&mut self) {
+        self.last_used.clear();
+        self.by_last_use.clear();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::ffi::OsString;
     use crate::cache::{IconsCache, ThemeCache};
-    use crate::search::test::test_search;
+    use crate::{Context, FileType, Icons, LookupOptions};
+    use crate::search::test::{
+        case_search, copy_search_dirs, copy_test_dirs, extra_search, test_search,
+    };
+    use std::fs::File;
+    use std::sync::Arc;
+    use std::time::SystemTime;
 
     #[test]
     fn test_icons_cached() {
@@ -232,6 +853,323 @@ mod test {
         assert_eq!(icon_original, icon_cached);
     }
 
+    #[test]
+    fn test_symbolic_fallback_cached() {
+        let mut icons = extra_search().search().icons_cached();
+        let options = LookupOptions {
+            symbolic_fallback: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            icons.find_icon("network-wireless", 16, 1, "ExtraTheme"),
+            None
+        );
+        let icon = icons
+            .find_icon_with_options("network-wireless", 16, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert_eq!(icon.icon_name(), "network-wireless-symbolic");
+    }
+
+    #[test]
+    fn test_prefer_scalable_cached() {
+        let mut icons = extra_search().search().icons_cached();
+        let options = LookupOptions {
+            prefer_scalable: true,
+            ..Default::default()
+        };
+
+        for (icon_name, size) in [("duo", 16), ("slanted", 6), ("slanted", 10)] {
+            let expected =
+                icons
+                    .icons()
+                    .find_icon_with_options(icon_name, size, 1, "ExtraTheme", &options);
+
+            assert!(expected.is_some());
+            assert_eq!(
+                icons.find_icon_with_options(icon_name, size, 1, "ExtraTheme", &options),
+                expected
+            );
+        }
+
+        assert_eq!(
+            icons
+                .find_icon_with_options("duo", 16, 1, "ExtraTheme", &options)
+                .unwrap()
+                .file_type(),
+            FileType::Svg
+        );
+        assert_eq!(
+            icons
+                .find_icon("duo", 16, 1, "ExtraTheme")
+                .unwrap()
+                .file_type(),
+            FileType::Png
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_cached() {
+        let mut icons = extra_search().search().icons_cached();
+        let options = LookupOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+
+        for icon_name in ["FAVORITE", "duo", "DUO", "dUo"] {
+            let expected =
+                icons
+                    .icons()
+                    .find_icon_with_options(icon_name, 16, 1, "ExtraTheme", &options);
+
+            assert!(expected.is_some());
+            assert_eq!(
+                icons.find_icon_with_options(icon_name, 16, 1, "ExtraTheme", &options),
+                expected
+            );
+        }
+
+        assert_eq!(icons.find_icon("DUO", 16, 1, "ExtraTheme"), None);
+
+        // exact names in parents and standalone icons win over names in the theme itself:
+        let mut icons = case_search().search().icons_cached();
+        for icon_name in ["tux", "TUX", "loose", "LOOSE"] {
+            let expected =
+                icons
+                    .icons()
+                    .find_icon_with_options(icon_name, 16, 1, "CaseChild", &options);
+
+            assert!(expected.is_some());
+            assert_eq!(
+                icons.find_icon_with_options(icon_name, 16, 1, "CaseChild", &options),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_case_insensitive_cached_reads_parents_once() {
+        let dirs = copy_search_dirs("case-insensitive-parents", case_search());
+        let mut icons = IconsCache::from(Icons::from_search_paths(dirs.clone()));
+        let options = LookupOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+
+        // a miss collects the names of CaseChild and CaseParent:
+        assert_eq!(
+            icons.find_icon_with_options("fresh", 16, 1, "CaseChild", &options),
+            None
+        );
+        let parent = &icons.themes[&OsString::from("CaseParent")];
+        assert!(parent.names_by_lowercase.is_some());
+
+        // so an icon added to the parent since isn't found by a name differing in case,
+        // while it would be if the parent's directories were read again:
+        let parent_dir = dirs[0].join("CaseParent/16x16/apps");
+        std::fs::copy(parent_dir.join("tux.png"), parent_dir.join("Fresh.png")).unwrap();
+        assert!(
+            icons
+                .icons()
+                .find_icon_with_options("fresh", 16, 1, "CaseChild", &options)
+                .is_some()
+        );
+        assert_eq!(
+            icons.find_icon_with_options("fresh", 16, 1, "CaseChild", &options),
+            None
+        );
+
+        std::fs::remove_dir_all(dirs[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_find_icon_in_context_cached() {
+        let mut icons = extra_search().search().icons_cached();
+
+        let status = icons.find_icon("favorite", 16, 1, "ExtraTheme").unwrap();
+        let emblem = icons
+            .find_icon_in_context("favorite", 16, 1, "ExtraTheme", Context::Emblems)
+            .unwrap();
+
+        assert_ne!(status, emblem);
+        assert_eq!(
+            Some(emblem),
+            icons
+                .icons()
+                .find_icon_in_context("favorite", 16, 1, "ExtraTheme", Context::Emblems)
+        );
+    }
+
+    #[test]
+    fn test_find_icon_first_of_cached() {
+        let mut icons = test_search().search().icons_cached();
+
+        let names = ["does-not-exist", "happy"];
+        let icon = icons
+            .find_icon_first_of(&names, 16, 1, "TestTheme")
+            .unwrap();
+        assert_eq!(icon.icon_name(), "happy");
+        assert_eq!(
+            Some(icon),
+            icons.icons().find_icon_first_of(&names, 16, 1, "TestTheme")
+        );
+    }
+
+    #[test]
+    fn test_closest_match_unchanged() {
+        let icons = test_search().search().icons();
+        let theme = icons.theme("TestTheme").unwrap();
+        let mut theme_cache = ThemeCache::from_theme(theme.clone());
+
+        for scale in [1, 2] {
+            for size in [1, 8, 15, 16, 17, 24, 32, 48, 64, 128, 512] {
+                assert_eq!(
+                    theme_cache.find_icon_here("happy", size, scale),
+                    theme.find_icon_here("happy", size, scale),
+                    "size {size}, scale {scale}"
+                );
+            }
+        }
+
+        let icon = theme_cache.find_icon_here("happy", 64, 1).unwrap();
+        assert!(icon.path().ends_with("TestTheme/32x32/foo/happy.png"));
+    }
+
+    #[test]
+    fn test_reload() {
+        let dirs = copy_test_dirs("reload");
+        let mut icons = IconsCache::with_capacity(Icons::from_search_paths(dirs.clone()), 8);
+        icons.find_icon("happy", 16, 1, "TestTheme").unwrap();
+        icons.find_icon("pixel", 1, 1, "OtherTheme").unwrap();
+
+        let theme = icons.icons().theme("OtherTheme").unwrap();
+        let index = &theme.info.index_location;
+        File::options()
+            .write(true)
+            .open(index)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(icons.icons().is_stale());
+
+        icons.reload();
+        assert!(!icons.icons().is_stale());
+
+        let test_theme = &icons.themes[&OsString::from("TestTheme")];
+        let other_theme = &icons.themes[&OsString::from("OtherTheme")];
+        // TestTheme didn't change, so its cache is kept,
+        assert_eq!(test_theme.cache.count(), 1);
+        assert!(Arc::ptr_eq(
+            &test_theme.theme,
+            &icons.icons().theme("TestTheme").unwrap()
+        ));
+        // but OtherTheme's cache starts over, with the same capacity.
+        assert!(other_theme.cache.is_empty());
+        assert_eq!(other_theme.recency.as_ref().map(|r| r.capacity), Some(8));
+
+        std::fs::remove_dir_all(dirs[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_find_mime_icon_cached() {
+        let mut icons = extra_search().search().icons_cached();
+
+        for mime in ["text/html", "application/pdf", "text/plain", "application"] {
+            let expected = icons.icons().find_mime_icon(mime, 16, 1, "ExtraTheme");
+            assert_eq!(icons.find_mime_icon(mime, 16, 1, "ExtraTheme"), expected);
+        }
+    }
+
+    #[test]
+    fn test_find_icon_detailed_cached() {
+        let mut icons = test_search().search().icons_cached();
+
+        for (icon_name, size) in [("happy", 16), ("happy", 64), ("pixel", 1), ("nope", 16)] {
+            let expected = icons
+                .icons()
+                .find_icon_detailed(icon_name, size, 1, "TestTheme");
+
+            // twice, to also match from the cache:
+            assert_eq!(
+                icons.find_icon_detailed(icon_name, size, 1, "TestTheme"),
+                expected
+            );
+            assert_eq!(
+                icons.find_icon_detailed(icon_name, size, 1, "TestTheme"),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_icons() {
+        let names = ["happy", "", "does-not-exist", "pixel", "happy"];
+        let mut icons = test_search().search().icons_cached();
+
+        let batch = icons.find_icons(&names, 16, 1, "TestTheme");
+        let single = names.map(|icon_name| icons.find_icon(icon_name, 16, 1, "TestTheme"));
+
+        assert_eq!(batch, single);
+        assert_eq!(batch[1], None, "empty names map to None");
+    }
+
+    #[test]
+    fn test_capacity_evicts_least_recently_used() {
+        let icons = test_search().search().icons();
+        let mut theme_cache = ThemeCache::with_capacity(icons.theme("TestTheme").unwrap(), 2);
+
+        theme_cache.find_icon_here("happy", 16, 1).unwrap();
+        theme_cache
+            .find_icon_here("beautiful sunset", 128, 1)
+            .unwrap();
+        // use "happy" again, making "beautiful sunset" the least recently used entry:
+        theme_cache.find_icon_here("happy", 32, 1).unwrap();
+        // misses count towards the limit too:
+        assert_eq!(theme_cache.find_icon_here("does-not-exist", 16, 1), None);
+
+        assert_eq!(theme_cache.cache.count(), 2);
+        assert!(
+            theme_cache.cache.contains_key_str("happy"),
+            "recently used entry survives"
+        );
+        assert!(theme_cache.cache.contains_key_str("does-not-exist"));
+        assert!(
+            !theme_cache.cache.contains_key_str("beautiful sunset"),
+            "least recently used entry is evicted"
+        );
+
+        let mut icons = IconsCache::with_capacity(icons, 1);
+        icons.pre_populate_cache();
+        assert!(icons.themes.values().all(|theme| theme.cache.count() <= 1));
+        assert!(!icons.themes[&OsString::from("TestTheme")].populated);
+
+        // names evicted right after scanning the theme are still listed:
+        assert_eq!(
+            icons.icon_names_with_prefix("", "TestTheme"),
+            icons.icons().icon_names_with_prefix("", "TestTheme")
+        );
+        let theme = icons.icons().theme("TestTheme").unwrap();
+        let mut theme_cache = ThemeCache::with_capacity(theme.clone(), 1);
+        assert_eq!(
+            theme_cache.icon_names_with_prefix(""),
+            theme.icon_names_with_prefix_here("")
+        );
+        assert_eq!(theme_cache.cache.count(), 1);
+    }
+
+    #[test]
+    fn test_icon_available_sizes_cached() {
+        let icons = test_search().search().icons();
+        let theme = icons.theme("TestTheme").unwrap();
+        let expected = theme.icon_available_sizes("happy");
+
+        let mut theme_cache: ThemeCache = theme.into();
+        assert_eq!(theme_cache.icon_available_sizes("happy"), expected);
+        assert!(theme_cache.cache.contains_key_str("happy"));
+        // now from the cache:
+        assert_eq!(theme_cache.icon_available_sizes("happy"), expected);
+    }
+
     #[test]
     fn test_cached_entry_persists() {
         let icons = test_search().search().icons();
@@ -283,4 +1221,29 @@ mod test {
 
         assert_eq!(icons.themes[&OsString::from("OtherTheme")].cache.count(), 1);
     }
+
+    #[test]
+    fn test_icon_names_with_prefix() {
+        let mut icons = test_search().search().icons_cached();
+
+        let theme = icons.theme_cache_mut("TestTheme").unwrap();
+        assert_eq!(theme.icon_names_with_prefix("ha"), vec!["happy"]);
+        assert!(
+            theme.cache.contains_key_str("happy"),
+            "cache contains happy icon"
+        );
+
+        assert_eq!(
+            icons.icon_names_with_prefix("ha", "TestTheme"),
+            vec!["happy"]
+        );
+
+        icons.pre_populate_cache();
+
+        assert_eq!(
+            icons.icon_names_with_prefix("", "TestTheme"),
+            icons.icons().icon_names_with_prefix("", "TestTheme"),
+            "populated cache agrees with the uncached version"
+        );
+    }
 }
@@ -0,0 +1,231 @@
+//! Saving and loading the contents of an [`IconsCache`] to and from disk.
+
+use crate::cache::{IconsCache, ThemeCache};
+use crate::theme::DirectoryRef;
+use crate::{IconFile, Icons};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the format of [`CacheFile`] changes, so that old files are rejected.
+const CACHE_FILE_VERSION: u32 = 1;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheFile {
+    version: u32,
+    themes: Vec<ThemeCacheFile>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ThemeCacheFile {
+    internal_name: OsString,
+    // Cached files refer to the directories of their theme by index.
+    // To make sure those indices still point to the same directories when loading, we save the
+    // names of all directories of the theme at the time of saving.
+    directories: Vec<String>,
+    populated: bool,
+    // Icons are stored as paths only, so that loading goes through `IconFile`'s constructor.
+    icons: Vec<(String, Vec<(DirectoryRef, PathBuf)>)>,
+}
+
+impl IconsCache {
+    /// Write the contents of all theme caches to a file at `path`.
+    ///
+    /// The file can be read back using [`IconsCache::load_cache`], which is typically much faster
+    /// than populating the cache again.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn save_cache<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let file = CacheFile {
+            version: CACHE_FILE_VERSION,
+            themes: self.themes.values().map(ThemeCache::to_file).collect(),
+        };
+
+        let bytes = serde_json::to_vec(&file).map_err(std::io::Error::other)?;
+        std::fs::write(path, bytes)
+    }
+
+    /// Creates a new [`IconsCache`] from [`Icons`], restoring the caches saved to `path` by
+    /// [`IconsCache::save_cache`].
+    ///
+    /// Saved caches of themes that are no longer present in `icons`, or whose directories have
+    /// changed since saving, are skipped. The caches of those themes start out empty instead.
+    ///
+    /// If the file can't be read, or isn't a cache file, the IO error or an [std::io::Error::other]
+    /// with the responsible parsing error inside is returned.
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn load_cache<P: AsRef<Path>>(icons: Icons, path: P) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let file: CacheFile = serde_json::from_slice(&bytes).map_err(std::io::Error::other)?;
+
+        if file.version != CACHE_FILE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unsupported cache file version {}", file.version),
+            ));
+        }
+
+        let mut cache: IconsCache = icons.into();
+
+        for theme_file in file.themes {
+            let Some(theme) = cache.themes.get_mut(theme_file.internal_name.as_os_str()) else {
+                #[cfg(feature = "log")]
+                log::warn!(
+                    "skipping cached theme {:?} because it no longer exists",
+                    theme_file.internal_name
+                );
+                continue;
+            };
+
+            theme.restore_from_file(theme_file);
+        }
+
+        Ok(cache)
+    }
+}
+
+impl ThemeCache {
+    fn to_file(&self) -> ThemeCacheFile {
+        let icons = self
+            .cache
+            .iter()
+            .map(|(icon_name, files)| {
+                let files = files
+                    .iter()
+                    .map(|(dir, icon)| (*dir, icon.path().to_owned()))
+                    .collect();
+
+                (icon_name.as_str().to_owned(), files)
+            })
+            .collect();
+
+        ThemeCacheFile {
+            internal_name: self.theme.info.internal_name.clone(),
+            directories: self.directory_names().map(str::to_owned).collect(),
+            populated: self.populated,
+            icons,
+        }
+    }
+
+    fn restore_from_file(&mut self, file: ThemeCacheFile) {
+        if !self
+            .directory_names()
+            .eq(file.directories.iter().map(String::as_str))
+        {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "skipping cached theme {:?} because its directories have changed",
+                file.internal_name
+            );
+            return;
+        }
+
+        let number_of_directories = file.directories.len();
+        let mut skipped_entries = false;
+
+        for (icon_name, files) in file.icons {
+            let files = files
+                .into_iter()
+                .map(|(dir, path)| {
+                    if dir >= number_of_directories {
+                        return None;
+                    }
+
+                    IconFile::from_path_buf(path).map(|icon| (dir, icon))
+                })
+                .collect::<Option<Vec<_>>>();
+
+            let Some(files) = files else {
+                #[cfg(feature = "log")]
+                log::warn!("skipping invalid cache entry for icon {icon_name:?}");
+                skipped_entries = true;
+                continue;
+            };
+
+            self.touch(&icon_name);
+            self.cache.insert(icon_name.into(), files);
+        }
+
+        // with entries missing, the cache no longer holds all icons of this theme.
+        self.populated = file.populated && !skipped_entries;
+        self.evict();
+    }
+
+    fn directory_names(&self) -> impl Iterator<Item = &str> {
+        self.theme
+            .info
+            .index
+            .directories
+            .iter()
+            .map(|dir| dir.directory_name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cache::IconsCache;
+    use crate::search::test::{extra_search, test_search};
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_save_and_load_cache() {
+        let path =
+            std::env::temp_dir().join(format!("icon-test-cache-{}.json", std::process::id()));
+
+        let mut icons = test_search().search().icons_cached();
+        icons.pre_populate_cache();
+        icons.find_icon("does-not-exist", 16, 1, "TestTheme");
+        icons.save_cache(&path).unwrap();
+
+        let loaded = IconsCache::load_cache(test_search().search().icons(), &path).unwrap();
+        for (name, theme) in &icons.themes {
+            let loaded_theme = &loaded.themes[name];
+            assert_eq!(loaded_theme.cache.count(), theme.cache.count());
+            assert_eq!(loaded_theme.populated, theme.populated);
+            for (icon_name, files) in theme.cache.iter() {
+                assert_eq!(loaded_theme.cache.get(icon_name), Some(files));
+            }
+        }
+
+        // none of the saved themes exist here, so nothing should be loaded:
+        let other = IconsCache::load_cache(extra_search().search().icons(), &path).unwrap();
+        assert!(
+            other.themes[&OsString::from("ExtraTheme")].cache.is_empty(),
+            "no cache entries for themes that weren't saved"
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_load_cache_with_invalid_entries() {
+        let path = std::env::temp_dir().join(format!(
+            "icon-test-invalid-cache-{}.json",
+            std::process::id()
+        ));
+
+        let mut icons = test_search().search().icons_cached();
+        icons.pre_populate_cache();
+        icons.save_cache(&path).unwrap();
+
+        // replace the path of the first file of every theme's first icon with one that isn't an icon
+        let mut file: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        for theme in file["themes"].as_array_mut().unwrap() {
+            assert_eq!(theme["populated"], true);
+            theme["icons"][0][1][0][1] = "/not/an/icon.txt".into();
+        }
+        std::fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+
+        let mut loaded = IconsCache::load_cache(test_search().search().icons(), &path).unwrap();
+        for theme in loaded.themes.values() {
+            assert!(!theme.populated, "populated with an entry missing");
+        }
+
+        // so prefix searches still find every icon:
+        assert_eq!(
+            loaded.icon_names_with_prefix("", "TestTheme"),
+            icons.icon_names_with_prefix("", "TestTheme")
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+use crate::cache::{IconsCache, ThemeCache};
+use crate::{IconFile, Icons, LookupOptions};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+
+/// Caching version of [`Icons`] that can be shared between threads.
+///
+/// Unlike [`IconsCache`], lookups only need `&self`: each theme's cache sits behind a [`RwLock`],
+/// so lookups of icons already in the cache only take a shared lock, and only cache misses
+/// have to wait for exclusive access to their theme's cache.
+///
+/// # Example
+///
+/// ```
+/// use icon::{Icons, SharedIconsCache};
+/// use std::sync::Arc;
+///
+/// let cache: Arc<SharedIconsCache> = Arc::new(Icons::new().into());
+///
+/// let handle = {
+///     let cache = Arc::clone(&cache);
+///     std::thread::spawn(move || cache.find_icon("firefox", 128, 1, "Adwaita"))
+/// };
+///
+/// cache.find_icon("firefox", 64, 1, "Adwaita");
+/// handle.join().unwrap();
+/// ```
+pub struct SharedIconsCache {
+    /// The [`Icons`] this cache was created from.
+    icons: Icons,
+    /// Mirrors `icons.themes`, but with all caches.
+    /// The implementation should make sure that all keys present in `icons.themes`,
+    /// also appear in this map. For the same reason, both `icons` and `themes` aren't `pub`;
+    /// otherwise users could break that invariant.
+    themes: HashMap<OsString, RwLock<ThemeCache>>,
+}
+
+impl SharedIconsCache {
+    /// Creates a new [`SharedIconsCache`] from [`Icons`].
+    pub fn from_icons(icons: Icons) -> Self {
+        icons.into()
+    }
+
+    /// Like [`find_icon`](self.find_icon), with `theme` being `"hicolor"`, which is the default icon theme.
+    ///
+    /// Caching version of [`Icons::find_default_icon`]
+    pub fn find_default_icon(&self, icon_name: &str, size: u32, scale: u32) -> Option<IconFile> {
+        self.find_icon(icon_name, size, scale, "hicolor")
+    }
+
+    /// Look up an icon by name, size, scale and theme.
+    ///
+    /// Caching version of [`Icons::find_icon`]. For more details on how icon matching works,
+    /// check out the documentation of [`Icons::find_icon`].
+    pub fn find_icon(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconFile> {
+        self.find_icon_with_options(icon_name, size, scale, theme, &LookupOptions::default())
+    }
+
+    /// Look up an icon by name, size, scale and theme, with additional [`LookupOptions`].
+    ///
+    /// Caching version of [`Icons::find_icon_with_options`].
+    pub fn find_icon_with_options(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        if icon_name.is_empty() {
+            return None;
+        }
+
+        let theme = self
+            .theme_cache(theme)
+            .or_else(|| self.theme_cache("hicolor"))?;
+
+        let parents = || Arc::clone(&read(theme).theme);
+
+        options
+            .icon_names(icon_name)
+            .find_map(|icon_name| Self::find_icon_here(theme, &icon_name, size, scale, options))
+            .or_else(|| {
+                // or find it in one of our parents.
+                // like `ThemeCache::find_icon`, parents don't use the cache for this.
+                parents().inherits_from.iter().find_map(|theme| {
+                    theme
+                        .find_icon_here_matching_case(icon_name, size, scale, options)
+                        .map(|(_, icon)| icon)
+                })
+            })
+            .or_else(|| {
+                self.icons
+                    .find_standalone_icon_matching_case(icon_name, options)
+            })
+            .or_else(|| {
+                if !options.case_insensitive {
+                    return None;
+                }
+
+                // the names that differ in case might still have to be collected. unlike the
+                // names matching in case, the parents use their own caches for this, so that
+                // their directories are only read once.
+                // each lock is released again before the next theme is checked.
+                std::iter::once(theme)
+                    .chain(
+                        parents()
+                            .inherits_from
+                            .iter()
+                            .flat_map(|theme| self.themes.get(&theme.info.internal_name)),
+                    )
+                    .find_map(|theme| {
+                        theme
+                            .write()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .find_icon_here_ignoring_case(icon_name, size, scale, options)
+                    })
+            })
+            .or_else(|| {
+                self.icons
+                    .find_standalone_icon_ignoring_case(icon_name, options)
+            })
+    }
+
+    /// Look up many icons of the same size, scale and theme at once.
+    ///
+    /// Caching version of [`IconsCache::find_icons`].
+    /// Besides resolving the theme only once, this function also takes each lock only once for all
+    /// names: first a shared lock to find all icons already in the cache, and then, only if there
+    /// were any cache misses, an exclusive lock to look up the remaining icons.
+    pub fn find_icons(
+        &self,
+        names: &[&str],
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Vec<Option<IconFile>> {
+        let Some(theme) = self
+            .theme_cache(theme)
+            .or_else(|| self.theme_cache("hicolor"))
+        else {
+            return vec![None; names.len()];
+        };
+
+        let options = LookupOptions::default();
+
+        // `None` means that we don't know yet.
+        let mut results: Vec<Option<Option<IconFile>>> = {
+            let cache = read(theme);
+
+            names
+                .iter()
+                .map(|icon_name| {
+                    if icon_name.is_empty() {
+                        Some(None)
+                    } else {
+                        cache.find_icon_here_cached(icon_name, size, scale, &options)
+                    }
+                })
+                .collect()
+        };
+
+        if results.iter().any(Option::is_none) {
+            let mut cache = theme.write().unwrap_or_else(PoisonError::into_inner);
+
+            for (result, icon_name) in results.iter_mut().zip(names) {
+                if result.is_none() {
+                    *result = Some(cache.find_icon_here_in(icon_name, size, scale, &options));
+                }
+            }
+        }
+
+        let theme = Arc::clone(&read(theme).theme);
+
+        results
+            .into_iter()
+            .flatten()
+            .zip(names)
+            .map(|(icon, icon_name)| {
+                if icon_name.is_empty() {
+                    return None;
+                }
+
+                icon.or_else(|| {
+                    theme
+                        .inherits_from
+                        .iter()
+                        .find_map(|theme| theme.find_icon_here(icon_name, size, scale))
+                })
+                .or_else(|| self.find_standalone_icon(icon_name))
+            })
+            .collect()
+    }
+
+    fn find_icon_here(
+        theme: &RwLock<ThemeCache>,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        // the guard must be dropped before trying to take the write lock!
+        let cached = read(theme).find_icon_here_cached(icon_name, size, scale, options);
+
+        match cached {
+            Some(icon) => icon,
+            // if another thread inserted this icon in the meantime, `find_icon_here` will just
+            // find it in the cache.
+            None => theme
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .find_icon_here_in(icon_name, size, scale, options),
+        }
+    }
+
+    fn theme_cache(&self, theme_name: &str) -> Option<&RwLock<ThemeCache>> {
+        let theme_name: &OsStr = theme_name.as_ref();
+        self.themes.get(theme_name)
+    }
+
+    /// Look up a standalone icon by name.
+    ///
+    /// Cache version of [`Icons::find_standalone_icon`].
+    pub fn find_standalone_icon(&self, icon_name: &str) -> Option<IconFile> {
+        self.icons.find_standalone_icon(icon_name)
+    }
+
+    /// Access the [`Icons`] this cache uses.
+    pub fn icons(&self) -> &Icons {
+        &self.icons
+    }
+}
+
+// A panic while holding the lock can't leave a `ThemeCache` in an invalid state:
+// at worst, an entry is missing from the cache. So, poisoning is ignored.
+fn read(theme: &RwLock<ThemeCache>) -> RwLockReadGuard<'_, ThemeCache> {
+    theme.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl From<Icons> for SharedIconsCache {
+    fn from(icons: Icons) -> Self {
+        IconsCache::from(icons).into()
+    }
+}
+
+/// Keeps the contents of the [`IconsCache`], so that you may, for example,
+/// [pre-populate](IconsCache::pre_populate_cache) a cache before sharing it.
+///
+/// Caches [with a capacity](IconsCache::with_capacity) keep their capacity, but note that every
+/// lookup in them takes an exclusive lock, as they need to keep track of which entries were used.
+impl From<IconsCache> for SharedIconsCache {
+    fn from(cache: IconsCache) -> Self {
+        let themes = cache
+            .themes
+            .into_iter()
+            .map(|(k, v)| (k, RwLock::new(v)))
+            .collect();
+
+        Self {
+            icons: cache.icons,
+            themes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::search::test::{case_search, test_search};
+    use crate::{LookupOptions, SharedIconsCache};
+
+    #[test]
+    fn test_shared_cache_concurrent_lookups() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedIconsCache>();
+
+        let icons = test_search().search().icons();
+        let queries = [
+            ("happy", 16),
+            ("happy", 32),
+            ("beautiful sunset", 128),
+            ("pixel", 1),
+            ("does-not-exist", 16),
+        ];
+        let expected =
+            queries.map(|(icon_name, size)| icons.find_icon(icon_name, size, 1, "TestTheme"));
+
+        let cache: SharedIconsCache = icons.into();
+
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let cache = &cache;
+                let expected = &expected;
+
+                scope.spawn(move || {
+                    for i in 0..100 {
+                        // every thread walks the queries in a different order
+                        let query = (thread + i) % queries.len();
+                        let (icon_name, size) = queries[query];
+
+                        assert_eq!(
+                            cache.find_icon(icon_name, size, 1, "TestTheme"),
+                            expected[query]
+                        );
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn test_shared_find_icons() {
+        let names = ["happy", "", "does-not-exist", "pixel", "happy"];
+        let cache: SharedIconsCache = test_search().search().icons().into();
+
+        let single = names.map(|icon_name| cache.find_icon(icon_name, 16, 1, "TestTheme"));
+        // from a warm cache:
+        assert_eq!(cache.find_icons(&names, 16, 1, "TestTheme"), single);
+
+        // and from a cold one:
+        let cache: SharedIconsCache = test_search().search().icons().into();
+        assert_eq!(cache.find_icons(&names, 16, 1, "TestTheme"), single);
+    }
+
+    #[test]
+    fn test_shared_case_insensitive() {
+        let options = LookupOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+        let names = ["tux", "TUX", "loose", "LOOSE"];
+        let icons = case_search().search().icons();
+        let expected = names
+            .map(|icon_name| icons.find_icon_with_options(icon_name, 16, 1, "CaseChild", &options));
+
+        let cache: SharedIconsCache = icons.into();
+        for (icon_name, expected) in names.into_iter().zip(expected) {
+            assert!(expected.is_some());
+            assert_eq!(
+                cache.find_icon_with_options(icon_name, 16, 1, "CaseChild", &options),
+                expected
+            );
+        }
+
+        // the names of the parent are collected in its own cache:
+        assert_eq!(
+            cache.find_icon_with_options("does-not-exist", 16, 1, "CaseChild", &options),
+            None
+        );
+        let parent = cache.theme_cache("CaseParent").unwrap();
+        assert!(parent.read().unwrap().names_by_lowercase.is_some());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{DirectoryIndex, IconSearch, Theme};
+use crate::{
+    Context, DirectoryIndex, DirectoryRef, DirectoryType, IconLocations, IconSearch, LookupOptions,
+    Theme,
+};
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
 use std::fmt::Display;
@@ -7,8 +10,9 @@ use std::sync::Arc;
 
 /// Main struct to locate icon files.
 ///
-/// Create this using [`Icons::new`] for the standard configuration, or use [`IconSearch`] if you
-/// wish to tune where icons can be found.
+/// Create this using [`Icons::new`] for the standard configuration, [`Icons::from_search_paths`]
+/// to search only specific directories, or use [`IconSearch`] if you wish to tune where icons can
+/// be found.
 ///
 /// # Example
 ///
@@ -22,6 +26,12 @@ pub struct Icons {
     pub standalone_icons: HashMap<String, IconFile>,
     /// Map of internal theme names to their corresponding [`Theme`]
     pub themes: HashMap<OsString, Arc<Theme>>,
+    /// The directories searched for standalone icons and icon themes, in order of precedence.
+    ///
+    /// These are searched again by [`Icons::is_stale`] and `IconsCache::reload`. When creating an
+    /// `Icons` by hand, this may be left empty, in which case only the themes' index files are
+    /// checked for changes.
+    pub search_paths: Vec<PathBuf>,
 }
 
 impl Icons {
@@ -33,6 +43,66 @@ impl Icons {
         IconSearch::new().search().icons()
     }
 
+    /// Creates a new `Icons`, performing a search in only the directories provided.
+    ///
+    /// Directories earlier in the list take precedence over later ones: if an icon theme is found
+    /// in multiple directories, the first `index.theme` file found is used, and if multiple
+    /// standalone icons share a name, the first one found is used.
+    ///
+    /// This is equivalent to `IconSearch::new_from(paths).search().icons()`.
+    pub fn from_search_paths(paths: Vec<PathBuf>) -> Self {
+        IconSearch::new_from(paths).search().icons()
+    }
+
+    /// Check whether the icons on disk have changed since this `Icons` was created.
+    ///
+    /// This is the case if:
+    /// - the index file of any theme was modified or removed,
+    /// - themes, or directories of themes, appeared in or disappeared from the
+    ///   [search paths](Icons::search_paths), or
+    /// - standalone icons appeared or disappeared.
+    ///
+    /// Icon files added to or removed from the directories of an existing theme are not detected,
+    /// as that would mean reading all of its directories.
+    ///
+    /// Without any search paths, only the index files are checked.
+    ///
+    /// This function re-scans the search paths, so it's meant to be polled every now and then,
+    /// not before every lookup. If it returns `true`, create a new `Icons` using
+    /// [`Icons::from_search_paths`], or reload a cache using `IconsCache::reload`.
+    pub fn is_stale(&self) -> bool {
+        let index_changed = self.themes.values().any(|theme| {
+            match std::fs::metadata(&theme.info.index_location) {
+                Ok(metadata) => metadata.modified().ok() != theme.info.index_modified,
+                // the index was removed (or can no longer be read)
+                Err(_) => true,
+            }
+        });
+
+        if index_changed || self.search_paths.is_empty() {
+            return index_changed;
+        }
+
+        let locations = IconSearch::new_from(self.search_paths.clone())
+            .search()
+            .into_icon_locations();
+
+        let themes_changed = self.themes.iter().any(|(name, theme)| {
+            locations.themes_directories.get(name) != Some(&theme.info.base_dirs)
+        });
+        // directories that aren't themes are also found, so only count new ones that are valid:
+        let themes_added = locations
+            .themes_directories
+            .keys()
+            .filter(|name| !self.themes.contains_key(*name))
+            .any(|name| locations.load_single_theme(name).is_ok());
+
+        themes_changed
+            || themes_added
+            || IconLocations::standalone_icons_by_name(locations.standalone_icons)
+                != self.standalone_icons
+    }
+
     /// Access a known icon theme by name
     pub fn theme(&self, theme_name: &str) -> Option<Arc<Theme>> {
         let theme_name: &OsStr = theme_name.as_ref();
@@ -64,6 +134,129 @@ impl Icons {
         size: u32,
         scale: u32,
         theme: &str,
+    ) -> Option<IconFile> {
+        self.find_icon_with_options(icon_name, size, scale, theme, &LookupOptions::default())
+    }
+
+    /// Look up an icon by name, size, scale and theme, only considering icons of a certain [`Context`].
+    ///
+    /// Like [`find_icon`](Icons::find_icon), but icons in theme directories of another (or no)
+    /// context are skipped, as are standalone icons.
+    /// This is a shorthand for [`find_icon_with_options`](Icons::find_icon_with_options) with
+    /// [`LookupOptions::context`] set.
+    pub fn find_icon_in_context(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+        context: Context,
+    ) -> Option<IconFile> {
+        let options = LookupOptions {
+            context: Some(context),
+            ..Default::default()
+        };
+
+        self.find_icon_with_options(icon_name, size, scale, theme, &options)
+    }
+
+    /// Look up the first icon found of a list of names, by size, scale and theme.
+    ///
+    /// This is meant for fallback chains, such as `["text-x-python", "text-x-generic"]`.
+    /// The theme is resolved only once, after which each name is looked up in the theme and all of
+    /// its parents, before trying the next name. If none of the names are found in the themes,
+    /// the standalone icons are checked for each name, in order.
+    ///
+    /// See [`find_icon`](Icons::find_icon) for details on how themes are resolved and icons are matched.
+    pub fn find_icon_first_of(
+        &self,
+        names: &[&str],
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconFile> {
+        let theme = self.theme(theme).or_else(|| self.theme("hicolor"))?;
+        theme.find_icon_first_of(names, size, scale).or_else(|| {
+            names
+                .iter()
+                .filter(|icon_name| !icon_name.is_empty())
+                .find_map(|icon_name| self.find_standalone_icon(icon_name))
+        })
+    }
+
+    /// Look up an icon by name, size, scale and theme, along with details on the directory it was
+    /// found in.
+    ///
+    /// The icon found is the same one [`find_icon`](Icons::find_icon) finds, but standalone icons
+    /// aren't considered, as they don't live in a directory of a theme.
+    /// This is meant for inspecting why a certain icon was chosen: see [`IconMatch`].
+    pub fn find_icon_detailed(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconMatch> {
+        if icon_name.is_empty() {
+            return None;
+        }
+
+        let theme = self.theme(theme).or_else(|| self.theme("hicolor"))?;
+
+        theme.find_icon_detailed(icon_name, size, scale)
+    }
+
+    /// Look up the icon for a MIME type, like `text/html`, by size, scale and theme.
+    ///
+    /// MIME types map to icon names by replacing the `/` with a `-`: the icon of `text/html` is
+    /// `text-html`. If that icon doesn't exist, the generic icon of the media type,
+    /// `text-x-generic`, is used instead.
+    /// Any parameters (like in `text/html; charset=utf-8`) are ignored.
+    ///
+    /// Returns `None` if `mime` isn't of the form `media/subtype`.
+    /// See [`find_icon_first_of`](Icons::find_icon_first_of) for how the names are looked up.
+    pub fn find_mime_icon(
+        &self,
+        mime: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+    ) -> Option<IconFile> {
+        let names = Self::mime_icon_names(mime)?;
+
+        self.find_icon_first_of(&names.each_ref().map(String::as_str), size, scale, theme)
+    }
+
+    /// The icon names to try, in order, for a MIME type.
+    pub(crate) fn mime_icon_names(mime: &str) -> Option<[String; 2]> {
+        // strip any parameters
+        let mime = mime.split(';').next()?.trim();
+        let (media, subtype) = mime.split_once('/')?;
+
+        if media.is_empty() || subtype.is_empty() || subtype.contains('/') {
+            return None;
+        }
+
+        // MIME types are case-insensitive, while icon names are (conventionally) lowercase.
+        let media = media.to_ascii_lowercase();
+        let subtype = subtype.to_ascii_lowercase();
+
+        Some([format!("{media}-{subtype}"), format!("{media}-x-generic")])
+    }
+
+    /// Look up an icon by name, size, scale and theme, with additional [`LookupOptions`].
+    ///
+    /// Like [`find_icon`](Icons::find_icon), but any fallbacks enabled in `options` are tried in
+    /// each theme before moving on to its parents, and for standalone icons last.
+    /// Names that differ in case are only tried once neither the themes nor the standalone icons
+    /// hold the exact name.
+    pub fn find_icon_with_options(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        theme: &str,
+        options: &LookupOptions,
     ) -> Option<IconFile> {
         if icon_name.is_empty() {
             return None;
@@ -71,8 +264,25 @@ impl Icons {
 
         let theme = self.theme(theme).or_else(|| self.theme("hicolor"))?;
         theme
-            .find_icon(icon_name, size, scale)
-            .or_else(|| self.find_standalone_icon(icon_name))
+            .find_icon_matching_case(icon_name, size, scale, options)
+            .or_else(|| self.find_standalone_icon_matching_case(icon_name, options))
+            .or_else(|| theme.find_icon_ignoring_case(icon_name, size, scale, options))
+            .or_else(|| self.find_standalone_icon_ignoring_case(icon_name, options))
+    }
+
+    /// List the names of all icons starting with `prefix` in a theme and its parents.
+    ///
+    /// - If no theme by the given name exists, the `"hicolor"` theme (default theme) is used instead.
+    /// - Standalone icons are not included.
+    ///
+    /// The returned names are de-duplicated and sorted lexicographically.
+    /// See [`Theme::icon_names_with_prefix`].
+    pub fn icon_names_with_prefix(&self, prefix: &str, theme: &str) -> Vec<String> {
+        let Some(theme) = self.theme(theme).or_else(|| self.theme("hicolor")) else {
+            return Vec::new();
+        };
+
+        theme.icon_names_with_prefix(prefix)
     }
 
     /// Look up a standalone icon by name.
@@ -85,13 +295,112 @@ impl Icons {
         self.standalone_icons.get(icon_name).cloned()
     }
 
+    // Like `find_standalone_icon`, but with the fallbacks enabled in `options`, except for trying
+    // names that differ in case.
+    pub(crate) fn find_standalone_icon_matching_case(
+        &self,
+        icon_name: &str,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        options
+            .standalone_icon_names(icon_name)
+            .find_map(|icon_name| self.find_standalone_icon(&icon_name))
+    }
+
+    // Like `find_standalone_icon_matching_case`, but only trying names that differ in case, if
+    // `options` allows it.
+    pub(crate) fn find_standalone_icon_ignoring_case(
+        &self,
+        icon_name: &str,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        if !options.case_insensitive {
+            return None;
+        }
+
+        options
+            .standalone_icon_names(icon_name)
+            .find_map(|icon_name| {
+                let icon_name = icon_name.to_lowercase();
+
+                self.standalone_icons
+                    .iter()
+                    .filter(|(name, _)| name.to_lowercase() == icon_name)
+                    .min_by_key(|(name, _)| name.as_str())
+                    .map(|(_, icon)| icon.clone())
+            })
+    }
+
+    /// Find out which icon of which theme a path belongs to.
+    ///
+    /// This is the reverse of finding an icon: given the path of an [`IconFile`] found in a theme,
+    /// it returns the theme, directory and name of that icon.
+    /// `None` is returned if the path doesn't lie in one of the directories of a known theme,
+    /// or is not an icon file at all. Standalone icons aren't identified either.
+    ///
+    /// Even though the icon may also be found through themes inheriting from its theme, the path is
+    /// always attributed to the theme whose directory holds the file.
+    ///
+    /// Paths are compared as-is: they aren't canonicalized, nor are they checked to exist.
+    pub fn identify_path(&self, path: &Path) -> Option<IconIdentity> {
+        let icon = IconFile::from_path(path)?;
+        let parent = path.parent()?;
+
+        self.themes.values().find_map(|theme| {
+            let directory = theme
+                .info
+                .base_dirs
+                .iter()
+                .flat_map(|base_dir| parent.strip_prefix(base_dir))
+                .find_map(|sub_dir| {
+                    theme
+                        .info
+                        .index
+                        .directories
+                        .iter()
+                        .position(|dir| Path::new(&dir.directory_name) == sub_dir)
+                })?;
+            let dir = &theme.info.index.directories[directory];
+
+            Some(IconIdentity {
+                theme: theme.info.internal_name.clone(),
+                directory,
+                icon_name: icon.icon_name().to_owned(),
+                size: dir.size,
+                scale: dir.scale,
+            })
+        })
+    }
+
     /// Find all icons in all themes, in all of their directories.
     ///
+    /// Each icon is yielded along with its theme and the directory it lives in, which holds the
+    /// [`Context`] of the icon, if any.
+    ///
     /// Also see [`find_all_icons_filtered`](Icons::find_all_icons_filtered).
     pub fn find_all_icons(&self) -> impl Iterator<Item = (Arc<Theme>, &DirectoryIndex, IconFile)> {
         self.find_all_icons_filtered(|_| true, |_| true, |_| true)
     }
 
+    /// Find all icons in a single theme, in all of its directories.
+    ///
+    /// Unlike [`find_all_icons`](Icons::find_all_icons), only the directories of this theme are read:
+    /// icons of the themes it inherits from aren't included.
+    /// Each icon is yielded along with the directory it lives in.
+    /// If there is no theme called `theme`, the iterator is empty.
+    pub fn find_all_icons_in_theme(
+        &self,
+        theme: &str,
+    ) -> impl Iterator<Item = (&DirectoryIndex, IconFile)> {
+        let theme_name: &OsStr = theme.as_ref();
+
+        self.themes.get(theme_name).into_iter().flat_map(|theme| {
+            theme
+                .all_icon_files()
+                .map(|(dir, icon)| (&theme.info.index.directories[dir], icon))
+        })
+    }
+
     /// Find all icons in all themes, in all of their directories, filtered at each stage by a predicate.
     ///
     /// This happens lazily: the function returns an iterator that only does the required work
@@ -187,12 +496,128 @@ impl Default for Icons {
     }
 }
 
+/// The theme, directory and name an icon file belongs to.
+///
+/// This type is returned by [`Icons::identify_path`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IconIdentity {
+    /// The internal name of the theme the icon belongs to.
+    pub theme: OsString,
+    /// The directory of the theme the icon lives in.
+    pub directory: DirectoryRef,
+    /// The name of the icon.
+    pub icon_name: String,
+    /// The nominal size of the icons in the directory.
+    pub size: u32,
+    /// The target scale of the icons in the directory.
+    pub scale: u32,
+}
+
+/// An icon found by a lookup, along with details on why it was chosen.
+///
+/// This type is returned by [`Icons::find_icon_detailed`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct IconMatch {
+    /// The icon found.
+    pub file: IconFile,
+    /// The directory the icon was found in.
+    pub directory: DirectoryInfo,
+    /// Whether the directory [matches](DirectoryIndex::matches_size) the requested size and
+    /// scale exactly. If not, the icon was the closest match available.
+    pub exact: bool,
+}
+
+impl IconMatch {
+    pub(crate) fn new(
+        theme: &Theme,
+        directory: DirectoryRef,
+        file: IconFile,
+        size: u32,
+        scale: u32,
+    ) -> Self {
+        let directory = DirectoryInfo::new(theme, directory);
+
+        Self {
+            file,
+            exact: directory.index.matches_size(size, scale),
+            directory,
+        }
+    }
+}
+
+/// The directory of a theme an icon was found in.
+///
+/// This type is part of [`IconMatch`], and is returned by [`Theme::icon_available_sizes`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DirectoryInfo {
+    theme: OsString,
+    directory: DirectoryRef,
+    index: DirectoryIndex,
+}
+
+impl DirectoryInfo {
+    pub(crate) fn new(theme: &Theme, directory: DirectoryRef) -> Self {
+        Self {
+            theme: theme.info.internal_name.clone(),
+            directory,
+            index: theme.info.index.directories[directory].clone(),
+        }
+    }
+
+    /// The internal name of the theme the directory belongs to.
+    pub fn theme(&self) -> &OsStr {
+        &self.theme
+    }
+
+    /// The reference to this directory in its theme.
+    pub fn directory_ref(&self) -> DirectoryRef {
+        self.directory
+    }
+
+    /// The full description of this directory in the theme's index.
+    pub fn index(&self) -> &DirectoryIndex {
+        &self.index
+    }
+
+    /// The type of this directory, which decides how `size`, `min_size`, `max_size` and
+    /// `threshold` are used.
+    pub fn directory_type(&self) -> DirectoryType {
+        self.index.directory_type
+    }
+
+    /// The nominal size of the icons in this directory.
+    pub fn size(&self) -> u32 {
+        self.index.size
+    }
+
+    /// The target scale of the icons in this directory.
+    pub fn scale(&self) -> u32 {
+        self.index.scale
+    }
+
+    /// The minimum size the icons in this directory can be scaled to.
+    pub fn min_size(&self) -> u32 {
+        self.index.min_size
+    }
+
+    /// The maximum size the icons in this directory can be scaled to.
+    pub fn max_size(&self) -> u32 {
+        self.index.max_size
+    }
+
+    /// How much the requested size may differ from `size`, for threshold directories.
+    pub fn threshold(&self) -> u32 {
+        self.index.threshold
+    }
+}
+
 /// The path to an icon along with its detected file type.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct IconFile {
     /// Absolute path to where this icon is found on disk.
     path: PathBuf,
-    /// The filetype of the icon, derived from its extension. May be `Png`, `Xpm` or `Svg`.
+    /// The filetype of the icon, derived from its extension. May be `Png`, `Xpm`, `Svg` or
+    /// (with the `ico` feature) `Ico`.
     file_type: FileType,
 }
 
@@ -212,10 +637,11 @@ impl IconFile {
 
     /// Create an `IconFile` from an owned filesystem path, deriving its filetype from its extension.
     ///
-    /// Returns `None` if the provided path does not have a name or extension valid for icons.
+    /// Returns `None` if the provided path does not have a name or extension valid for icons,
+    /// including names that aren't valid UTF-8.
     pub fn from_path_buf(path_buf: PathBuf) -> Option<IconFile> {
-        // An icon file must have a file stem.
-        path_buf.file_stem()?;
+        // An icon file must have a file stem, which is its name, so it has to be valid UTF-8.
+        path_buf.file_stem()?.to_str()?;
 
         let file_type = FileType::from_path_ext(&path_buf)?;
 
@@ -230,10 +656,56 @@ impl IconFile {
         &self.path
     }
 
-    /// Returns this icon's file type
+    /// Returns this icon's file type.
+    ///
+    /// The file type is derived from the extension once, when the `IconFile` is created, so this is
+    /// a cheap way to decide how to render an icon, or to filter out icons of unsupported types.
+    /// Files with other extensions are never icons, so there's no variant for them.
     pub fn file_type(&self) -> FileType {
         self.file_type
     }
+
+    /// Open and decode this icon into an image.
+    ///
+    /// The decoder is picked based on this icon's [file type](IconFile::file_type).
+    /// Only [FileType::Png] and (with the `ico` feature) [FileType::Ico] icons can be decoded;
+    /// for other file types, [LoadError::Unsupported] is returned.
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    pub fn load(&self) -> Result<image::DynamicImage, LoadError> {
+        let format = match self.file_type {
+            FileType::Png => image::ImageFormat::Png,
+            #[cfg(feature = "ico")]
+            FileType::Ico => image::ImageFormat::Ico,
+            file_type => return Err(LoadError::Unsupported(file_type)),
+        };
+
+        let file = std::fs::File::open(&self.path)?;
+        let image =
+            image::ImageReader::with_format(std::io::BufReader::new(file), format).decode()?;
+
+        Ok(image)
+    }
+}
+
+/// An error occurred while loading an icon.
+///
+/// This type is returned by [IconFile::load].
+#[cfg(feature = "image")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    /// The icon file couldn't be opened, for example because it no longer exists.
+    #[error("failed to open icon file")]
+    Io(#[from] std::io::Error),
+    /// The icon file was opened, but its contents couldn't be decoded.
+    #[error("failed to decode icon")]
+    Decode(#[from] image::ImageError),
+    /// Icons of this file type can't be decoded.
+    ///
+    /// `image` has no support for [FileType::Svg] and [FileType::Xpm] files.
+    #[error("decoding {0} icons is not supported")]
+    Unsupported(FileType),
 }
 
 /// Supported image file formats for icons.
@@ -300,9 +772,261 @@ impl Display for FileType {
 
 #[cfg(test)]
 mod test {
-    use crate::IconFile;
-    use crate::search::test::test_search;
-    use std::collections::HashMap;
+    use crate::search::test::{copy_test_dirs, extra_search, test_search};
+    use crate::{DirectoryType, FileType, IconFile, Icons};
+    use std::collections::{HashMap, HashSet};
+    use std::fs::File;
+    use std::path::Path;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_find_mime_icon() {
+        let icons = extra_search().search().icons();
+
+        let icon = icons
+            .find_mime_icon("text/html", 16, 1, "ExtraTheme")
+            .unwrap();
+        assert_eq!(icon.icon_name(), "text-html");
+
+        // there's no application-pdf icon, so the generic one is used:
+        let icon = icons
+            .find_mime_icon("application/pdf", 16, 1, "ExtraTheme")
+            .unwrap();
+        assert_eq!(icon.icon_name(), "application-x-generic");
+        assert_eq!(
+            icons.find_mime_icon("Application/PDF; version=1.7", 16, 1, "ExtraTheme"),
+            Some(icon)
+        );
+
+        // neither text-plain nor text-x-generic exist:
+        assert_eq!(
+            icons.find_mime_icon("text/plain", 16, 1, "ExtraTheme"),
+            None
+        );
+
+        for malformed in ["", "application", "/pdf", "application/", "a/b/c", ";/"] {
+            assert_eq!(
+                icons.find_mime_icon(malformed, 16, 1, "ExtraTheme"),
+                None,
+                "{malformed:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_all_icons_in_theme() {
+        let icons = test_search().search().icons();
+
+        let names = icons
+            .find_all_icons_in_theme("TestTheme")
+            .map(|(_, icon)| icon.icon_name().to_owned())
+            .collect::<HashSet<_>>();
+
+        // same as the number of different icons `pre_populate_cache` finds for TestTheme
+        #[cfg(feature = "ico")]
+        assert_eq!(names.len(), 3);
+        #[cfg(not(feature = "ico"))]
+        assert_eq!(names.len(), 2);
+
+        for (dir, icon) in icons.find_all_icons_in_theme("TestTheme") {
+            assert!(icon.path().parent().unwrap().ends_with(&dir.directory_name));
+        }
+
+        assert_eq!(icons.find_all_icons_in_theme("does-not-exist").count(), 0);
+    }
+
+    #[test]
+    fn test_find_icon_detailed() {
+        let icons = test_search().search().icons();
+
+        let found = icons
+            .find_icon_detailed("happy", 16, 1, "TestTheme")
+            .unwrap();
+        assert!(found.exact);
+        assert_eq!(found.directory.theme(), "TestTheme");
+        assert_eq!(found.directory.index().directory_name, "16x16/α");
+        assert_eq!(found.directory.directory_type(), DirectoryType::Threshold);
+        assert_eq!(found.directory.size(), 16);
+        assert_eq!(found.directory.scale(), 1);
+        assert_eq!(
+            Some(found.file),
+            icons.find_icon("happy", 16, 1, "TestTheme")
+        );
+
+        // there's no 64px directory with happy in it, so the closest one is used:
+        let found = icons
+            .find_icon_detailed("happy", 64, 1, "TestTheme")
+            .unwrap();
+        assert!(!found.exact);
+        assert_eq!(found.directory.index().directory_name, "32x32/foo");
+
+        // found in a parent:
+        let found = icons
+            .find_icon_detailed("pixel", 1, 1, "TestTheme")
+            .unwrap();
+        assert_eq!(found.directory.theme(), "OtherTheme");
+
+        assert_eq!(icons.find_icon_detailed("nope", 16, 1, "TestTheme"), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_non_utf8_icon_names() {
+        use crate::LookupOptions;
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let bad_name = OsStr::from_bytes(b"bad\xff.png");
+        let path = Path::new("/icons").join(bad_name);
+        assert_eq!(IconFile::from_path(&path), None);
+
+        // such files are skipped when listing the icons of a theme:
+        let dirs = copy_test_dirs("non-utf8");
+        File::create(dirs[0].join("TestTheme/16x16/α").join(bad_name)).unwrap();
+        let icons = Icons::from_search_paths(dirs.clone());
+
+        let expected = test_search()
+            .search()
+            .icons()
+            .icon_names_with_prefix("", "TestTheme");
+        assert_eq!(icons.icon_names_with_prefix("", "TestTheme"), expected);
+        assert!(
+            icons
+                .find_all_icons_in_theme("TestTheme")
+                .all(|(_, icon)| !icon.icon_name().is_empty())
+        );
+        let options = LookupOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            icons.find_icon_with_options("does-not-exist", 16, 1, "TestTheme", &options),
+            None
+        );
+
+        #[cfg(feature = "cache")]
+        {
+            let mut icons: crate::IconsCache = icons.into();
+            assert_eq!(icons.icon_names_with_prefix("", "TestTheme"), expected);
+            assert_eq!(
+                icons.find_icon_with_options("does-not-exist", 16, 1, "TestTheme", &options),
+                None
+            );
+        }
+
+        std::fs::remove_dir_all(dirs[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_file_type() {
+        let icons = test_search().search().icons();
+
+        let icon = icons.find_icon("happy", 16, 1, "TestTheme").unwrap();
+        assert_eq!(icon.file_type(), FileType::Png);
+
+        let icon = IconFile::from_path(Path::new("/icons/sunset.SVG")).unwrap();
+        assert_eq!(icon.file_type(), FileType::Svg);
+        assert_eq!(IconFile::from_path(Path::new("/icons/sunset.jpg")), None);
+
+        let icon = IconFile::from_path(Path::new("/icons/tux.ico"));
+        #[cfg(feature = "ico")]
+        assert_eq!(icon.unwrap().file_type(), FileType::Ico);
+        #[cfg(not(feature = "ico"))]
+        assert_eq!(icon, None);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let dirs = copy_test_dirs("is-stale");
+        let icons = Icons::from_search_paths(dirs.clone());
+        assert!(!icons.is_stale());
+
+        // touch an index file:
+        let index = &icons.theme("OtherTheme").unwrap().info.index_location;
+        File::options()
+            .write(true)
+            .open(index)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(icons.is_stale());
+
+        // a fresh `Icons` is up to date again, until a theme is added:
+        let icons = Icons::from_search_paths(dirs.clone());
+        assert!(!icons.is_stale());
+
+        let new_theme = dirs[0].join("NewTheme");
+        std::fs::create_dir(&new_theme).unwrap();
+        assert!(!icons.is_stale(), "not a theme without an index");
+        std::fs::copy(index, new_theme.join("index.theme")).unwrap();
+        assert!(icons.is_stale());
+
+        // without search paths, only the index files are checked:
+        let icons = Icons {
+            search_paths: Vec::new(),
+            ..Icons::from_search_paths(dirs.clone())
+        };
+        assert!(!icons.is_stale());
+
+        std::fs::remove_dir_all(dirs[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_identify_path() {
+        let icons = test_search().search().icons();
+
+        let happy = icons.find_icon("happy", 16, 1, "TestTheme").unwrap();
+        let identity = icons.identify_path(happy.path()).unwrap();
+        assert_eq!(identity.theme, "TestTheme");
+        assert_eq!(identity.icon_name, "happy");
+        assert_eq!((identity.size, identity.scale), (16, 1));
+
+        let theme = icons.theme("TestTheme").unwrap();
+        assert_eq!(
+            theme.info.index.directories[identity.directory].directory_name,
+            "16x16/α"
+        );
+
+        // attributed to OtherTheme, even when found through TestTheme:
+        let pixel = icons.find_icon("pixel", 1, 1, "TestTheme").unwrap();
+        assert_eq!(
+            icons.identify_path(pixel.path()).unwrap().theme,
+            "OtherTheme"
+        );
+
+        assert_eq!(
+            icons.identify_path("/usr/share/pixmaps/happy.png".as_ref()),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_load_icon() {
+        use crate::LoadError;
+
+        let icons = test_search().search().icons();
+
+        let happy = icons.find_icon("happy", 32, 1, "TestTheme").unwrap();
+        let image = happy.load().unwrap();
+        assert_eq!((image.width(), image.height()), (32, 32));
+
+        let sunset = icons
+            .find_all_icons()
+            .map(|(_, _, icon)| icon)
+            .find(|icon| icon.file_type() == crate::FileType::Xpm)
+            .unwrap();
+        assert!(matches!(sunset.load(), Err(LoadError::Unsupported(_))));
+
+        let missing = IconFile::from_path("/this/path/probably/doesnt/exist.png".as_ref()).unwrap();
+        assert!(matches!(missing.load(), Err(LoadError::Io(_))));
+
+        #[cfg(feature = "ico")]
+        {
+            let tux = icons.find_icon("tux", 32, 1, "TestTheme").unwrap();
+            assert!(tux.load().is_ok());
+        }
+    }
 
     #[test]
     fn test_find_all_icons() {
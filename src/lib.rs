@@ -24,7 +24,9 @@
 //! By default, **no features** are enabled.
 //!
 //! - **`log`**: Enable logging, which introduces a dependency on the `log` crate.
-//! - **`cache`**: Enables the caching versions of [`Icons`] and [`Theme`] ([`IconsCache`] and [`ThemeCache`]), which introduces a dependency on `qp-trie`.
+//! - **`cache`**: Enables the caching versions of [`Icons`] and [`Theme`] ([`IconsCache`], [`SharedIconsCache`] and [`ThemeCache`]), which introduces a dependency on `qp-trie`.
+//! - **`image`**: Enables decoding icons using [`IconFile::load`], which introduces a dependency on `image`.
+//! - **`serde`**: Enables `cache`, along with saving and loading the contents of an [`IconsCache`] to and from disk, which introduces a dependency on `serde` and `serde_json`.
 //!
 //! # Icon matching
 //!
@@ -178,6 +178,7 @@ impl IconSearch<Initial> {
         }
 
         IconLocations {
+            search_dirs: self.dirs.clone(),
             standalone_icons: files,
             themes_directories,
         }
@@ -208,7 +209,7 @@ impl IconSearch<LocationsFound> {
 
     /// Consume this `IconSearch` to expose its [`IconLocations`].
     ///
-    /// Contained search directories are lost.
+    /// Contained search directories are moved into the [`IconLocations`].
     pub fn into_icon_locations(self) -> IconLocations {
         self.icon_locations.expect("guaranteed by type-state")
     }
@@ -244,14 +245,14 @@ impl IconSearch<LocationsFound> {
 impl IconSearch<Finished> {
     /// Consume this `IconSearch` to expose its [`Icons`].
     ///
-    /// Contained search directories are lost.
+    /// Contained search directories remain available through [`Icons::search_paths`].
     pub fn icons(self) -> Icons {
         self.icons.expect("guaranteed by type-state")
     }
 
     /// Consume this `IconSearch` to expose its [`Icons`] and turn it into [`IconsCache`](crate::cache::IconsCache).
     ///
-    /// Contained search directories are lost.
+    /// Contained search directories remain available through [`Icons::search_paths`].
     #[cfg(feature = "cache")]
     #[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
     pub fn icons_cached(self) -> crate::cache::IconsCache {
@@ -267,6 +268,8 @@ impl IconSearch<Finished> {
 ///   This is a list because icon themes may be split up over multiple base directories.
 #[derive(Debug)]
 pub struct IconLocations {
+    /// The directories that were searched, in order of precedence.
+    pub search_dirs: Vec<PathBuf>,
     /// List of icons not belonging to any theme.
     pub standalone_icons: Vec<IconFile>,
     /// Map of icon theme identifiers to the directories where the icons live.
@@ -296,25 +299,33 @@ impl IconLocations {
     pub fn icons(self) -> Icons {
         let themes = self.resolve();
 
-        let standalone_icons = self
-            .standalone_icons
-            .into_iter()
-            .map(|file| {
-                let key = file
-                    .path()
-                    .file_stem()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .unwrap_or(String::new());
-                (key, file)
-            })
-            .collect();
-
         Icons {
-            standalone_icons,
+            standalone_icons: Self::standalone_icons_by_name(self.standalone_icons),
             themes,
+            search_paths: self.search_dirs,
         }
     }
 
+    /// Maps the names of standalone icons to their files.
+    pub(crate) fn standalone_icons_by_name(
+        standalone_icons: Vec<IconFile>,
+    ) -> HashMap<String, IconFile> {
+        // standalone icons are in the order of the directories they were found in:
+        // make sure that icons from earlier directories take precedence.
+        let mut by_name = HashMap::new();
+        for file in standalone_icons {
+            let key = file
+                .path()
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or(String::new());
+
+            by_name.entry(key).or_insert(file);
+        }
+
+        by_name
+    }
+
     /// Like [`icons`](IconLocations::icons), but immediately wrapped into the cached type.
     #[cfg(feature = "cache")]
     #[cfg_attr(docsrs, doc(cfg(feature = "cache")))]
@@ -332,6 +343,8 @@ impl IconLocations {
     /// - Find all (transitive) dependencies of themes, performing the same operation(s) for them, and
     /// - Pruning duplicate references in the dependency graph: after `resolve`, each theme has a
     ///   _direct acyclic graph_ of its dependents computed.
+    ///   Inheritance loops from malformed themes are broken up by ignoring the inheritance that
+    ///   would close the loop.
     pub fn resolve(&self) -> HashMap<OsString, Arc<Theme>> {
         self.resolve_only(self.themes_directories.keys())
     }
@@ -426,6 +439,39 @@ impl IconLocations {
         // if not, there's no use in inserting hicolor into the inheritance tree later
         let hicolor_idx = theme_names.iter().position(|name| name == "hicolor");
 
+        // the direct parents of each theme, skipping parents that were invalid.
+        let mut theme_parents: Vec<Vec<usize>> = theme_info
+            .iter()
+            .flatten()
+            .map(|info| {
+                info.index
+                    .inherits
+                    .iter()
+                    .filter_map(|parent| {
+                        theme_names
+                            .iter()
+                            .position(|name| *name.as_os_str() == **parent)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // A malformed theme might (transitively) inherit from itself. Left alone, that would make
+        // it impossible to find a topological order below, so the edges closing such loops are
+        // dropped, guaranteeing that the inheritance graph is acyclic.
+        // hicolor is implicitly inherited by every theme, so anything it inherits closes a loop.
+        if let Some(hicolor_idx) = hicolor_idx
+            && !theme_parents[hicolor_idx].is_empty()
+        {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "ignoring the themes inherited by \"hicolor\", as hicolor is inherited by all themes"
+            );
+
+            theme_parents[hicolor_idx].clear();
+        }
+        break_inheritance_cycles(&theme_names, &mut theme_parents);
+
         // Time to find the optimal ancestry for each theme.
         // As hicolor _should_ have all icons by default, and all themes depend on hicolor at some depth,
         // DFS would de facto end up in hicolor before ever trying the second theme in an Inherits set.
@@ -442,19 +488,7 @@ impl IconLocations {
             while let Some(node_idx) = chain.get(cursor).copied() {
                 cursor += 1;
 
-                let Some(Some(info)) = theme_info.get(node_idx) else {
-                    continue;
-                };
-
-                for parent in &info.index.inherits {
-                    let Some(parent_idx) = theme_names
-                        .iter()
-                        .position(|name| *name.as_os_str() == **parent)
-                    else {
-                        // this parent was invalid
-                        continue;
-                    };
-
+                for parent_idx in theme_parents[node_idx].iter().copied() {
                     // add this parent, removing any previous occurrences
                     chain.retain(|idx| *idx != parent_idx);
                     chain.push(parent_idx);
@@ -605,11 +639,75 @@ impl Default for IconSearch {
     }
 }
 
+/// Removes the edges from `theme_parents` (indexed like `theme_names`) that close an inheritance loop,
+/// leaving an acyclic graph.
+///
+/// Themes are visited in order of their names, so the same edges are removed every time.
+fn break_inheritance_cycles(theme_names: &[OsString], theme_parents: &mut [Vec<usize>]) {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Visit {
+        NotYet,
+        InProgress,
+        Done,
+    }
+
+    /// Visits `theme_idx` and its parents depth-first, collecting the removed edges in `removed`.
+    fn visit(
+        theme_idx: usize,
+        theme_parents: &mut [Vec<usize>],
+        visits: &mut [Visit],
+        removed: &mut Vec<(usize, usize)>,
+    ) {
+        visits[theme_idx] = Visit::InProgress;
+
+        let mut i = 0;
+        while let Some(parent_idx) = theme_parents[theme_idx].get(i).copied() {
+            match visits[parent_idx] {
+                // this parent is still being visited, so it (transitively) inherits this theme.
+                Visit::InProgress => {
+                    theme_parents[theme_idx].remove(i);
+                    removed.push((theme_idx, parent_idx));
+                    continue;
+                }
+                Visit::NotYet => visit(parent_idx, theme_parents, visits, removed),
+                Visit::Done => {}
+            }
+
+            i += 1;
+        }
+
+        visits[theme_idx] = Visit::Done;
+    }
+
+    let mut order: Vec<usize> = (0..theme_names.len()).collect();
+    order.sort_by(|a, b| theme_names[*a].cmp(&theme_names[*b]));
+
+    let mut visits = vec![Visit::NotYet; theme_names.len()];
+    let mut removed = Vec::new();
+    for theme_idx in order {
+        if visits[theme_idx] == Visit::NotYet {
+            visit(theme_idx, theme_parents, &mut visits, &mut removed);
+        }
+    }
+
+    #[cfg(feature = "log")]
+    for (theme_idx, parent_idx) in removed {
+        log::warn!(
+            "ignoring that theme {:?} inherits {:?}, as that theme inherits it again",
+            theme_names[theme_idx],
+            theme_names[parent_idx],
+        );
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test {
+    use crate::Icons;
     use crate::search::IconSearch;
     use std::collections::HashSet;
-    use std::path::PathBuf;
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
 
     static PROJ_ROOT: &'static str = env!("CARGO_MANIFEST_DIR");
 
@@ -620,6 +718,56 @@ pub(crate) mod test {
         ])
     }
 
+    pub fn extra_search() -> IconSearch {
+        IconSearch::new_empty()
+            .add_directories([PathBuf::from(PROJ_ROOT).join("resources/test_icons_extra")])
+    }
+
+    pub fn case_search() -> IconSearch {
+        IconSearch::new_empty()
+            .add_directories([PathBuf::from(PROJ_ROOT).join("resources/test_icons_case")])
+    }
+
+    /// Copies the directories of [`test_search`] to a new temporary directory, for tests that
+    /// modify them. The returned directories all share the same parent.
+    pub fn copy_test_dirs(test_name: &str) -> Vec<PathBuf> {
+        copy_search_dirs(test_name, test_search())
+    }
+
+    /// Like [`copy_test_dirs`], but for the directories of any `search`.
+    pub fn copy_search_dirs(test_name: &str, search: IconSearch) -> Vec<PathBuf> {
+        fn copy_dir(from: &Path, to: &Path) {
+            std::fs::create_dir_all(to).unwrap();
+
+            for entry in std::fs::read_dir(from).unwrap() {
+                let entry = entry.unwrap();
+                let target = to.join(entry.file_name());
+
+                // follows symlinks, copying what they point to.
+                if std::fs::metadata(entry.path()).unwrap().is_dir() {
+                    copy_dir(&entry.path(), &target);
+                } else {
+                    std::fs::copy(entry.path(), target).unwrap();
+                }
+            }
+        }
+
+        let root =
+            std::env::temp_dir().join(format!("icon-test-{test_name}-{}", std::process::id()));
+        // left over from an earlier run
+        let _ = std::fs::remove_dir_all(&root);
+
+        search
+            .dirs
+            .iter()
+            .map(|dir| {
+                let target = root.join(dir.file_name().unwrap());
+                copy_dir(dir, &target);
+                target
+            })
+            .collect()
+    }
+
     // these tests assume certain applications are installed on the system they are run on.
 
     #[test]
@@ -643,6 +791,46 @@ pub(crate) mod test {
         // no panic
     }
 
+    #[test]
+    fn test_from_search_paths() {
+        let paths = test_search().dirs;
+        let icons = Icons::from_search_paths(paths.clone());
+
+        assert_eq!(icons.search_paths, paths);
+        assert_eq!(icons.themes.len(), 2);
+
+        // TestTheme is split up over both directories, in order:
+        let theme = icons.theme("TestTheme").unwrap();
+        assert!(theme.info.base_dirs[0].starts_with(&paths[0]));
+        assert!(theme.info.base_dirs[1].starts_with(&paths[1]));
+        assert!(theme.info.index_location.starts_with(&paths[0]));
+    }
+
+    #[test]
+    fn test_inheritance_cycle() {
+        let themes = IconSearch::new_empty()
+            .add_directories([PathBuf::from(PROJ_ROOT).join("resources/test_icons_cyclic")])
+            .search()
+            .into_icon_locations()
+            .resolve();
+
+        assert_eq!(themes.len(), 2);
+
+        // CycleA is visited first, so CycleB's loop back to CycleA is dropped.
+        let cycle_a = &themes[OsStr::new("CycleA")];
+        let cycle_b = &themes[OsStr::new("CycleB")];
+        assert_eq!(cycle_a.inherits_from.len(), 1);
+        assert!(Arc::ptr_eq(&cycle_a.inherits_from[0], cycle_b));
+        assert!(cycle_b.inherits_from.is_empty());
+
+        // lookups still go through the remaining parent:
+        assert!(cycle_a.find_icon("happy", 16, 1).is_some());
+
+        // and terminate for icons that don't exist:
+        assert_eq!(cycle_a.find_icon("does-not-exist", 16, 1), None);
+        assert_eq!(cycle_b.find_icon("does-not-exist", 16, 1), None);
+    }
+
     #[test]
     fn test_find_test_theme() {
         let dirs = test_search();
@@ -1,11 +1,15 @@
 use crate::ThemeParseError::MissingRequiredAttribute;
-use crate::icon::{FileType, IconFile};
+use crate::icon::{DirectoryInfo, FileType, IconFile, IconMatch};
 use freedesktop_entry_parser::low_level::{SectionBytes, SectionBytesIter};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 
-pub(crate) type DirectoryRef = usize;
+/// A reference to one of the directories of a theme: its index in [`ThemeIndex::directories`].
+pub type DirectoryRef = usize;
 
 /// An icon theme.
 pub struct Theme {
@@ -36,11 +40,154 @@ impl Theme {
     /// - `size`: the size, in pixels, desired. The returned icon may not be this exact size in case an exact match couldn't be found.
     /// - `scale`: the scale at which the icon will be displayed.
     pub fn find_icon(&self, icon_name: &str, size: u32, scale: u32) -> Option<IconFile> {
-        self.find_icon_here(icon_name, size, scale).or_else(|| {
-            // or find it in one of our parents
-            self.inherits_from
-                .iter()
-                .find_map(|theme| theme.find_icon_here(icon_name, size, scale))
+        self.find_icon_with_options(icon_name, size, scale, &LookupOptions::default())
+    }
+
+    /// Find the first icon of a list of names in this theme or any of its dependencies.
+    ///
+    /// Each name is looked up in the full inheritance tree before the next name is tried, so
+    /// `names` should be ordered from most to least specific.
+    /// Also see [find_icon](Theme::find_icon).
+    pub fn find_icon_first_of(&self, names: &[&str], size: u32, scale: u32) -> Option<IconFile> {
+        names
+            .iter()
+            .filter(|icon_name| !icon_name.is_empty())
+            .find_map(|icon_name| self.find_icon(icon_name, size, scale))
+    }
+
+    /// Find an icon in this theme or any of its dependencies, with additional [`LookupOptions`].
+    ///
+    /// Like [find_icon](Theme::find_icon), but any fallbacks enabled in `options` are tried for
+    /// each theme before moving on to its parents. Names that differ in case are only tried once
+    /// no theme holds the exact name.
+    pub fn find_icon_with_options(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        self.find_icon_matching_case(icon_name, size, scale, options)
+            .or_else(|| self.find_icon_ignoring_case(icon_name, size, scale, options))
+    }
+
+    // Like `find_icon_with_options`, but without trying names that differ in case.
+    pub(crate) fn find_icon_matching_case(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        std::iter::once(self)
+            .chain(self.inherits_from.iter().map(Arc::as_ref))
+            .find_map(|theme| theme.find_icon_here_matching_case(icon_name, size, scale, options))
+            .map(|(_, icon)| icon)
+    }
+
+    // Like `find_icon_with_options`, but only trying names that differ in case, if `options`
+    // allows it.
+    pub(crate) fn find_icon_ignoring_case(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        if !options.case_insensitive {
+            return None;
+        }
+
+        std::iter::once(self)
+            .chain(self.inherits_from.iter().map(Arc::as_ref))
+            .find_map(|theme| theme.find_icon_here_ignoring_case(icon_name, size, scale, options))
+            .map(|(_, icon)| icon)
+    }
+
+    /// Find an icon in this theme only, with additional [`LookupOptions`].
+    ///
+    /// Do not use this function if you need normal icon finding behaviour: use [find_icon_with_options](Theme::find_icon_with_options) instead.
+    pub fn find_icon_here_with_options(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconFile> {
+        self.find_icon_ref_here_with_options(icon_name, size, scale, options)
+            .map(|(_, icon)| icon)
+    }
+
+    /// Find an icon in this theme or any of its dependencies, along with details on the directory
+    /// it was found in.
+    ///
+    /// The icon found is the same one [find_icon](Theme::find_icon) finds: this function is meant
+    /// for inspecting why that icon was chosen.
+    pub fn find_icon_detailed(&self, icon_name: &str, size: u32, scale: u32) -> Option<IconMatch> {
+        let options = LookupOptions::default();
+
+        std::iter::once(self)
+            .chain(self.inherits_from.iter().map(Arc::as_ref))
+            .find_map(|theme| theme.find_icon_detailed_here(icon_name, size, scale, &options))
+    }
+
+    // Like `find_icon_here_with_options`, but with the details of the directory the icon was found in.
+    pub(crate) fn find_icon_detailed_here(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<IconMatch> {
+        let (directory, icon) =
+            self.find_icon_ref_here_with_options(icon_name, size, scale, options)?;
+
+        Some(IconMatch::new(self, directory, icon, size, scale))
+    }
+
+    // Like `find_icon_here_with_options`, but also returns the directory the icon was found in.
+    fn find_icon_ref_here_with_options(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<(DirectoryRef, IconFile)> {
+        self.find_icon_here_matching_case(icon_name, size, scale, options)
+            .or_else(|| self.find_icon_here_ignoring_case(icon_name, size, scale, options))
+    }
+
+    // Like `find_icon_ref_here_with_options`, but without trying names that differ in case.
+    pub(crate) fn find_icon_here_matching_case(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<(DirectoryRef, IconFile)> {
+        options
+            .icon_names(icon_name)
+            .find_map(|icon_name| self.find_icon_here_in(&icon_name, size, scale, options))
+    }
+
+    // Like `find_icon_ref_here_with_options`, but only trying names that differ in case, if
+    // `options` allows it.
+    pub(crate) fn find_icon_here_ignoring_case(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<(DirectoryRef, IconFile)> {
+        if !options.case_insensitive {
+            return None;
+        }
+
+        // finding the names that differ in case requires reading all directories.
+        let mut names_by_lowercase = self.icon_names_by_lowercase();
+        options.icon_names(icon_name).find_map(|icon_name| {
+            let icon_name = names_by_lowercase.remove(&icon_name.to_lowercase())?;
+            self.find_icon_here_in(&icon_name, size, scale, options)
         })
     }
 
@@ -48,10 +195,33 @@ impl Theme {
     ///
     /// Do not use this function if you need normal icon finding behaviour: use [find_icon](Theme::find_icon) instead.
     pub fn find_icon_here(&self, icon_name: &str, size: u32, scale: u32) -> Option<IconFile> {
+        self.find_icon_here_in(icon_name, size, scale, &LookupOptions::default())
+            .map(|(_, icon)| icon)
+    }
+
+    // Like `find_icon_here`, but only considers the directories allowed by `options`, and also
+    // returns the directory the icon was found in.
+    // Any name fallbacks in `options` are ignored.
+    fn find_icon_here_in(
+        &self,
+        icon_name: &str,
+        size: u32,
+        scale: u32,
+        options: &LookupOptions,
+    ) -> Option<(DirectoryRef, IconFile)> {
         // first, try to find an exact icon size match:
-        let exact_sub_dirs = self.exact_sub_dirs_for(size, scale);
+        let mut exact_sub_dirs = self
+            .exact_sub_dirs_for(size, scale, options)
+            .collect::<Vec<_>>();
+        // the sort is stable, so preferred directories are still checked in order.
+        exact_sub_dirs.sort_by_key(|(_, sub_dir)| !options.prefers_directory(sub_dir));
+
         if let Some(exact_match_icon) = exact_sub_dirs
-            .flat_map(|exact_sub_dir| self.find_icon_in_directory(icon_name, exact_sub_dir))
+            .into_iter()
+            .flat_map(|(dir, exact_sub_dir)| {
+                self.find_icon_in_directory(icon_name, exact_sub_dir)
+                    .map(|icon| (dir, icon))
+            })
             .next()
         {
             // and return it if found!
@@ -64,10 +234,19 @@ impl Theme {
         // we opt to do the hopefully _less expensive_ operation of sorting the subdirectories instead,
         // from the smallest size_distance to largest.
         // that gives us the assurance that the first icon found, is the best one.
-        let mut sub_dirs = self.info.index.directories.iter().collect::<Vec<_>>();
-        sub_dirs.sort_by_key(|sub_dir| sub_dir.size_distance(size, scale));
+        // as the sort is stable, equally close directories keep their order, matching the
+        // closest-match search of `ThemeCache`.
+        let mut sub_dirs = self
+            .info
+            .index
+            .directories
+            .iter()
+            .enumerate()
+            .filter(|(_, sub_dir)| options.allows_directory(sub_dir))
+            .collect::<Vec<_>>();
+        sub_dirs.sort_by_key(|(_, sub_dir)| options.size_distance(sub_dir, size, scale));
 
-        for sub_dir in sub_dirs {
+        for (dir, sub_dir) in sub_dirs {
             for base_dir in &self.info.base_dirs {
                 for file_name in &Self::possible_file_names_for(icon_name) {
                     let path = base_dir
@@ -76,7 +255,7 @@ impl Theme {
                     if path.exists()
                         && let Some(file) = IconFile::from_path(&path)
                     {
-                        return Some(file);
+                        return Some((dir, file));
                     }
                 }
             }
@@ -85,7 +264,123 @@ impl Theme {
         None
     }
 
-    #[allow(unused)] // Used with certain crate features.
+    /// List the names of all icons in this theme or any of its dependencies starting with `prefix`.
+    ///
+    /// Every directory of every theme involved is read, so this is a fairly expensive operation.
+    /// The names returned are de-duplicated and sorted lexicographically.
+    pub fn icon_names_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut names = BTreeSet::new();
+
+        for theme in std::iter::once(self).chain(self.inherits_from.iter().map(Arc::as_ref)) {
+            names.extend(
+                theme
+                    .icon_files_with_prefix(prefix)
+                    .map(|(_, icon)| icon.icon_name().to_owned()),
+            );
+        }
+
+        names.into_iter().collect()
+    }
+
+    /// List the names of all icons in this theme only starting with `prefix`.
+    ///
+    /// Like [icon_names_with_prefix](Theme::icon_names_with_prefix), but without considering the
+    /// theme's dependencies.
+    pub fn icon_names_with_prefix_here(&self, prefix: &str) -> Vec<String> {
+        self.icon_files_with_prefix(prefix)
+            .map(|(_, icon)| icon.icon_name().to_owned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Find all icon files in this theme, in all of its directories.
+    pub(crate) fn all_icon_files(&self) -> impl Iterator<Item = (DirectoryRef, IconFile)> {
+        self.info
+            .index
+            .directories
+            .iter()
+            .enumerate()
+            .flat_map(|(index, di)| {
+                self.info
+                    .base_dirs
+                    .iter()
+                    .map(move |base_dir| base_dir.join(di.directory_name.as_str()))
+                    .flat_map(|dir| dir.read_dir()) // Skip directories we can't read.
+                    .flatten() // Flatten out the dir iterator,
+                    .flatten() // and skip Err entries.
+                    .flat_map(|dir_entry| IconFile::from_path_buf(dir_entry.path()))
+                    .map(move |icon| (index, icon))
+            })
+    }
+
+    /// Map the lowercase names of all icons in this theme (not its parents) to their actual names.
+    ///
+    /// If multiple names only differ in case, the lexicographically first one is used.
+    pub(crate) fn icon_names_by_lowercase(&self) -> HashMap<String, String> {
+        let names = self
+            .all_icon_files()
+            .map(|(_, icon)| icon.icon_name().to_owned())
+            .collect::<BTreeSet<_>>();
+
+        let mut names_by_lowercase = HashMap::new();
+        for name in names {
+            // names are visited in order, so the first one inserted wins.
+            names_by_lowercase
+                .entry(name.to_lowercase())
+                .or_insert(name);
+        }
+
+        names_by_lowercase
+    }
+
+    pub(crate) fn icon_files_with_prefix(
+        &self,
+        prefix: &str,
+    ) -> impl Iterator<Item = (DirectoryRef, IconFile)> {
+        self.all_icon_files()
+            .filter(move |(_, icon)| icon.icon_name().starts_with(prefix))
+    }
+
+    /// List all sizes and scales an icon is available at in this theme or any of its dependencies.
+    ///
+    /// Each size and scale is listed once, along with the file for it and the directory it lives
+    /// in, ordered by size and then scale. If multiple themes (or directories) provide an icon
+    /// at the same size and scale, the file that would be found first by
+    /// [find_icon](Theme::find_icon) is listed.
+    ///
+    /// The sizes listed are the nominal [sizes](DirectoryInfo::size) of the directories the files
+    /// live in. Icons from scalable directories may be used at any size within their directory's
+    /// [`min_size`](DirectoryInfo::min_size) and [`max_size`](DirectoryInfo::max_size).
+    pub fn icon_available_sizes(&self, icon_name: &str) -> Vec<(DirectoryInfo, IconFile)> {
+        let files = std::iter::once(self)
+            .chain(self.inherits_from.iter().map(Arc::as_ref))
+            .flat_map(|theme| {
+                theme
+                    .find_icon_files(icon_name)
+                    .map(move |(dir, icon)| (theme, dir, icon))
+            });
+
+        Self::available_sizes(files)
+    }
+
+    pub(crate) fn available_sizes<'a>(
+        files: impl Iterator<Item = (&'a Theme, DirectoryRef, IconFile)>,
+    ) -> Vec<(DirectoryInfo, IconFile)> {
+        let mut sizes = BTreeMap::new();
+
+        for (theme, dir, icon) in files {
+            let index = &theme.info.index.directories[dir];
+
+            // the first file found for each size wins.
+            sizes
+                .entry((index.size, index.scale))
+                .or_insert_with(|| (DirectoryInfo::new(theme, dir), icon));
+        }
+
+        sizes.into_values().collect()
+    }
+
     pub(crate) fn find_icon_files(
         &self,
         icon_name: &str,
@@ -105,12 +400,16 @@ impl Theme {
         &self,
         size: u32,
         scale: u32,
-    ) -> impl Iterator<Item = &DirectoryIndex> + Clone {
+        options: &LookupOptions,
+    ) -> impl Iterator<Item = (DirectoryRef, &DirectoryIndex)> + Clone {
         self.info
             .index
             .directories
             .iter()
-            .filter(move |sub_dir| sub_dir.matches_size(size, scale))
+            .enumerate()
+            .filter(move |(_, sub_dir)| {
+                options.allows_directory(sub_dir) && sub_dir.matches_size(size, scale)
+            })
     }
 
     #[cfg(not(feature = "ico"))]
@@ -149,6 +448,107 @@ impl Theme {
     }
 }
 
+/// Options to tune icon lookups beyond what the Icon Theme specification describes.
+///
+/// The `Default` implementation disables all options, resulting in lookups that follow the
+/// specification exactly.
+///
+/// # Example
+///
+/// ```rust
+/// use icon::{Icons, LookupOptions};
+///
+/// let options = LookupOptions {
+///     symbolic_fallback: true,
+///     ..Default::default()
+/// };
+///
+/// Icons::new().find_icon_with_options("network-wireless", 32, 1, "Adwaita", &options);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LookupOptions {
+    /// If an icon isn't found, try its symbolic variant (the name suffixed with `-symbolic`) instead.
+    /// Likewise, if a symbolic icon isn't found, try its regular variant (with the suffix stripped).
+    ///
+    /// This fallback is tried in each theme before its parents are checked.
+    pub symbolic_fallback: bool,
+    /// Only consider icons in directories of this [`Context`].
+    ///
+    /// If `None`, icons in all directories are considered, including directories that don't
+    /// declare a context. Otherwise, standalone icons (which have no context) are skipped.
+    pub context: Option<Context>,
+    /// Prefer icons in [scalable](DirectoryType::Scalable) directories, which are usually SVGs,
+    /// over raster icons.
+    ///
+    /// Among directories matching the requested size exactly, scalable directories are tried first.
+    /// If no directory matches exactly, the size distance of scalable directories is halved, so
+    /// that they're chosen over raster icons that are only slightly closer in size.
+    pub prefer_scalable: bool,
+    /// If an icon isn't found, try icons whose names only differ in case, like `Firefox` for `firefox`.
+    ///
+    /// An icon with the exact name always takes precedence: names that differ in case are only
+    /// tried once neither the theme, nor any of its parents, nor the standalone icons hold the
+    /// exact name (or its symbolic fallback). If multiple icons in a theme only differ in case
+    /// from the name looked up, the lexicographically first of their names is used.
+    ///
+    /// Finding names that differ in case means reading all directories of a theme. Lookups through
+    /// an `IconsCache` or `SharedIconsCache` only do this once per theme, but uncached lookups do
+    /// this for every icon that isn't found, as does a lone `ThemeCache` for the themes it inherits.
+    pub case_insensitive: bool,
+}
+
+impl LookupOptions {
+    const SYMBOLIC_SUFFIX: &'static str = "-symbolic";
+
+    /// The names to try, in order, when looking up `icon_name` with these options.
+    pub(crate) fn icon_names<'a>(&self, icon_name: &'a str) -> impl Iterator<Item = Cow<'a, str>> {
+        let symbolic_fallback =
+            self.symbolic_fallback
+                .then(|| match icon_name.strip_suffix(Self::SYMBOLIC_SUFFIX) {
+                    Some(regular_name) => Cow::Borrowed(regular_name),
+                    None => Cow::Owned(format!("{icon_name}{}", Self::SYMBOLIC_SUFFIX)),
+                });
+
+        std::iter::once(Cow::Borrowed(icon_name))
+            .chain(symbolic_fallback)
+            .filter(|icon_name| !icon_name.is_empty())
+    }
+
+    /// The names to try, in order, when looking up `icon_name` in the standalone icons.
+    ///
+    /// Standalone icons have no context, so there are none when looking for a specific context.
+    pub(crate) fn standalone_icon_names<'a>(
+        &'a self,
+        icon_name: &'a str,
+    ) -> impl Iterator<Item = Cow<'a, str>> {
+        self.icon_names(icon_name)
+            .filter(|_| self.context.is_none())
+    }
+
+    /// Whether icons in `directory` should be tried before those in other matching directories.
+    pub(crate) fn prefers_directory(&self, directory: &DirectoryIndex) -> bool {
+        self.prefer_scalable && directory.directory_type == DirectoryType::Scalable
+    }
+
+    /// The size distance of `directory` to the requested size, weighted by these options.
+    pub(crate) fn size_distance(&self, directory: &DirectoryIndex, size: u32, scale: u32) -> u32 {
+        let distance = directory.size_distance(size, scale);
+
+        if self.prefers_directory(directory) {
+            distance / 2
+        } else {
+            distance
+        }
+    }
+
+    /// Whether icons in `directory` may be considered with these options.
+    pub(crate) fn allows_directory(&self, directory: &DirectoryIndex) -> bool {
+        self.context
+            .as_ref()
+            .is_none_or(|context| directory.context.as_ref() == Some(context))
+    }
+}
+
 /// Information about an icon theme.
 ///
 /// Its formal description (called the index) can be found in the `index` field.
@@ -169,6 +569,10 @@ pub struct ThemeInfo {
     /// first `index.theme` file they find when searching base directories; this field holds the
     /// path to that file.
     pub index_location: PathBuf,
+    /// When the `index.theme` file was last modified, as of reading it.
+    ///
+    /// `None` if the platform or file system doesn't record modification times.
+    pub index_modified: Option<SystemTime>,
     /// The contents of the `index.theme` file.
     pub index: ThemeIndex,
     // additional groups?
@@ -220,12 +624,17 @@ impl ThemeInfo {
             .find(|index_path| index_path.exists())
             .ok_or_else(|| std::io::Error::other(ThemeParseError::NotAnIconTheme))?;
 
+        // stat before reading, so that changes made while reading are noticed later on.
+        let index_modified = std::fs::metadata(&index_location)
+            .and_then(|metadata| metadata.modified())
+            .ok();
         let index = ThemeIndex::parse_from_file(index_location.as_path())?;
 
         Ok(Self {
             internal_name,
             base_dirs: folders,
             index_location,
+            index_modified,
             index,
         })
     }
@@ -363,7 +772,7 @@ pub struct DirectoryIndex {
     /// *Target scale of the icons in this directory. Defaults to the value 1 if not present. Any directory with a scale other than 1 should be listed in the ScaledDirectories list rather than Directories for backwards compatibility.*
     pub scale: u32,
     /// *The context the icon is normally used in. This is in detail discussed in [Section 4.1, “Context”](https://specifications.freedesktop.org/icon-theme/latest/#context).*
-    pub context: Option<String>,
+    pub context: Option<Context>,
     /// *The type of icon sizes for the icons in this directory. Valid types are `Fixed`, `Scalable` and `Threshold`. The type decides what other keys in the section are used. If not specified, the default is `Threshold`.*
     pub directory_type: DirectoryType,
     /// *Specifies the maximum (unscaled) size that the icons in this directory can be scaled to. Defaults to the value of `size` if not present.*
@@ -410,7 +819,7 @@ impl DirectoryIndex {
             is_scaled_dir: scale != 1,
             size,
             scale,
-            context: context.map(Into::into),
+            context: context.map(Context::from),
             directory_type,
             max_size,
             min_size,
@@ -497,6 +906,56 @@ pub enum DirectoryType {
     Threshold,
 }
 
+/// The context icons in an icon theme subdirectory are normally used in.
+///
+/// All doc comments in *italics* below are copy-pasted from the XDG Icon Theme Specification.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Context {
+    /// *Icons which are generally used in menus and dialogs for interacting with the user.*
+    Actions,
+    /// *Animated images used to represent loading web pages, or other background processing which may be less suited to more verbose progress reporting in the Status context.*
+    Animations,
+    /// *Icons that describe what an application is, for use in the Programs menu, window decorations, and the task list.*
+    Applications,
+    /// *Icons that are used for categories in the Programs menu, or the Control Center, for separating applications, preferences, and settings for display to the user.*
+    Categories,
+    /// *Icons for hardware that is contained within or connected to the computing device.*
+    Devices,
+    /// *Icons for tags and properties of files, that are displayed in the file manager.*
+    Emblems,
+    /// *Icons for emotions that are expressed through text chat applications such as :-) or :-P in IRC or instant messengers.*
+    Emotes,
+    /// *Icons for international denominations such as flags.*
+    International,
+    /// *Icons for different types of data, such as audio or image files.*
+    MimeTypes,
+    /// *Icons used to represent locations, either on the local filesystem, or through remote connections.*
+    Places,
+    /// *Icons for presenting status to the user.*
+    Status,
+    /// Any context not defined by the specification, holding the value as found in the index.
+    Other(String),
+}
+
+impl From<&str> for Context {
+    fn from(value: &str) -> Self {
+        match value {
+            "Actions" => Context::Actions,
+            "Animations" => Context::Animations,
+            "Applications" => Context::Applications,
+            "Categories" => Context::Categories,
+            "Devices" => Context::Devices,
+            "Emblems" => Context::Emblems,
+            "Emotes" => Context::Emotes,
+            "International" => Context::International,
+            "MimeTypes" => Context::MimeTypes,
+            "Places" => Context::Places,
+            "Status" => Context::Status,
+            other => Context::Other(other.into()),
+        }
+    }
+}
+
 /// The `Default` implementation for `DirectoryType` returns [DirectoryType::Threshold].
 ///
 /// This is because the XDG Icon Theme specification mandates that if the type for a directory is
@@ -545,8 +1004,8 @@ fn find_attr_req<'a>(
 mod test {
     use crate::Icons;
     use crate::icon::FileType;
-    use crate::search::test::test_search;
-    use crate::{DirectoryType, ThemeIndex};
+    use crate::search::test::{case_search, extra_search, test_search};
+    use crate::{Context, DirectoryType, LookupOptions, ThemeIndex};
     use std::error::Error;
     use std::path::Path;
     use std::time::{Duration, Instant};
@@ -568,6 +1027,229 @@ mod test {
         assert_eq!(small_ico.file_type(), FileType::Png);
     }
 
+    #[test]
+    fn test_icon_names_with_prefix() {
+        let icons = test_search().search().icons();
+
+        assert_eq!(
+            icons.icon_names_with_prefix("ha", "TestTheme"),
+            vec!["happy"]
+        );
+        // "pixel" lives in OtherTheme, which TestTheme inherits from:
+        assert_eq!(
+            icons.icon_names_with_prefix("pi", "TestTheme"),
+            vec!["pixel"]
+        );
+        assert!(icons.icon_names_with_prefix("xyz", "TestTheme").is_empty());
+
+        let theme = icons.theme("TestTheme").unwrap();
+        assert!(theme.icon_names_with_prefix_here("pi").is_empty());
+    }
+
+    #[test]
+    fn test_icon_available_sizes() {
+        let icons = test_search().search().icons();
+        let theme = icons.theme("TestTheme").unwrap();
+
+        let sizes = theme.icon_available_sizes("happy");
+        assert_eq!(
+            sizes
+                .iter()
+                .map(|(dir, _)| (dir.size(), dir.scale()))
+                .collect::<Vec<_>>(),
+            vec![(16, 1), (32, 1)]
+        );
+        assert!(sizes[0].1.path().ends_with("TestTheme/16x16/α/happy.png"));
+        assert_eq!(sizes[0].0.theme(), "TestTheme");
+        assert_eq!(sizes[0].0.index().directory_name, "16x16/α");
+
+        // includes parents:
+        assert_eq!(theme.icon_available_sizes("pixel").len(), 1);
+        assert!(theme.icon_available_sizes("does-not-exist").is_empty());
+
+        // scalable directories come with their size range:
+        let icons = extra_search().search().icons();
+        let theme = icons.theme("ExtraTheme").unwrap();
+        let sizes = theme.icon_available_sizes("slanted");
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[0].0.directory_type(), DirectoryType::Fixed);
+        assert_eq!(sizes[0].0.size(), 12);
+        let (scalable, file) = &sizes[1];
+        assert_eq!(scalable.directory_type(), DirectoryType::Scalable);
+        assert_eq!(
+            (scalable.size(), scalable.min_size(), scalable.max_size()),
+            (16, 16, 64)
+        );
+        assert_eq!(file.file_type(), FileType::Svg);
+    }
+
+    #[test]
+    fn test_find_icon_first_of() {
+        let icons = test_search().search().icons();
+
+        let icon = icons
+            .find_icon_first_of(&["does-not-exist", "happy"], 16, 1, "TestTheme")
+            .unwrap();
+        assert_eq!(icon.icon_name(), "happy");
+
+        // "pixel" is only found in OtherTheme, but is tried before "happy":
+        let icon = icons
+            .find_icon_first_of(&["pixel", "happy"], 16, 1, "TestTheme")
+            .unwrap();
+        assert_eq!(icon.icon_name(), "pixel");
+
+        assert_eq!(
+            icons.find_icon_first_of(&["", "nope"], 16, 1, "TestTheme"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_icon_in_context() {
+        let icons = extra_search().search().icons();
+
+        // "favorite" is both a status icon and an emblem:
+        let status = icons.find_icon("favorite", 16, 1, "ExtraTheme").unwrap();
+        assert!(
+            status
+                .path()
+                .ends_with("ExtraTheme/16x16/status/favorite.png")
+        );
+
+        let emblem = icons
+            .find_icon_in_context("favorite", 16, 1, "ExtraTheme", Context::Emblems)
+            .unwrap();
+        assert!(
+            emblem
+                .path()
+                .ends_with("ExtraTheme/16x16/emblems/favorite.png")
+        );
+
+        // "network-wireless-symbolic" only exists as a status icon:
+        assert_eq!(
+            icons.find_icon_in_context(
+                "network-wireless-symbolic",
+                16,
+                1,
+                "ExtraTheme",
+                Context::Emblems
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prefer_scalable() {
+        let icons = extra_search().search().icons();
+        let options = LookupOptions {
+            prefer_scalable: true,
+            ..Default::default()
+        };
+
+        // both 16x16/apps and scalable/apps match exactly:
+        let icon = icons.find_icon("duo", 16, 1, "ExtraTheme").unwrap();
+        assert_eq!(icon.file_type(), FileType::Png);
+        let icon = icons
+            .find_icon_with_options("duo", 16, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert_eq!(icon.file_type(), FileType::Svg);
+        assert!(icon.path().ends_with("ExtraTheme/scalable/apps/duo.svg"));
+
+        // no directory matches: 12x12/apps is closer, but not by enough to beat scalable/apps
+        let icon = icons.find_icon("slanted", 6, 1, "ExtraTheme").unwrap();
+        assert_eq!(icon.file_type(), FileType::Png);
+        let icon = icons
+            .find_icon_with_options("slanted", 6, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert_eq!(icon.file_type(), FileType::Svg);
+
+        // unless the raster icon is much closer:
+        let icon = icons
+            .find_icon_with_options("slanted", 10, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert_eq!(icon.file_type(), FileType::Png);
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let icons = test_search().search().icons();
+        let options = LookupOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+
+        assert_eq!(icons.find_icon("HAPPY", 16, 1, "TestTheme"), None);
+        let icon = icons
+            .find_icon_with_options("HAPPY", 16, 1, "TestTheme", &options)
+            .unwrap();
+        assert_eq!(icon.icon_name(), "happy");
+        assert_eq!(Some(icon), icons.find_icon("happy", 16, 1, "TestTheme"));
+
+        // "Duo" and "duo" both exist: an exact match wins, otherwise the first name is used.
+        let icons = extra_search().search().icons();
+        let icon = icons
+            .find_icon_with_options("duo", 16, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert_eq!(icon.icon_name(), "duo");
+        let icon = icons
+            .find_icon_with_options("DUO", 16, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert_eq!(icon.icon_name(), "Duo");
+
+        // CaseChild only holds "Tux" and "Loose", while "tux" is in its parent and "loose" is a
+        // standalone icon: the exact names win.
+        let icons = case_search().search().icons();
+        let icon = icons
+            .find_icon_with_options("tux", 16, 1, "CaseChild", &options)
+            .unwrap();
+        assert!(icon.path().ends_with("CaseParent/16x16/apps/tux.png"));
+        let icon = icons
+            .find_icon_with_options("loose", 16, 1, "CaseChild", &options)
+            .unwrap();
+        assert!(icon.path().ends_with("test_icons_case/loose.png"));
+
+        let theme = icons.theme("CaseChild").unwrap();
+        let icon = theme
+            .find_icon_with_options("tux", 16, 1, &options)
+            .unwrap();
+        assert_eq!(icon.icon_name(), "tux");
+        let icon = theme
+            .find_icon_with_options("TUX", 16, 1, &options)
+            .unwrap();
+        assert!(icon.path().ends_with("CaseChild/16x16/apps/Tux.png"));
+    }
+
+    #[test]
+    fn test_symbolic_fallback() {
+        let icons = extra_search().search().icons();
+        let options = LookupOptions {
+            symbolic_fallback: true,
+            ..Default::default()
+        };
+
+        // only the symbolic variant exists
+        assert_eq!(
+            icons.find_icon("network-wireless", 16, 1, "ExtraTheme"),
+            None
+        );
+        let icon = icons
+            .find_icon_with_options("network-wireless", 16, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert!(
+            icon.path()
+                .ends_with("ExtraTheme/16x16/status/network-wireless-symbolic.png")
+        );
+
+        // only the regular variant exists
+        let icon = icons
+            .find_icon_with_options("audio-volume-muted-symbolic", 16, 1, "ExtraTheme", &options)
+            .unwrap();
+        assert!(
+            icon.path()
+                .ends_with("ExtraTheme/16x16/status/audio-volume-muted.png")
+        );
+    }
+
     #[test]
     #[cfg(feature = "ico")]
     fn test_find_ico_icon() {
@@ -660,7 +1342,7 @@ mod test {
         assert_eq!(first_dir_index.is_scaled_dir, false);
         assert_eq!(first_dir_index.size, 48);
         assert_eq!(first_dir_index.scale, 1);
-        assert_eq!(first_dir_index.context.as_deref(), Some("Applications"));
+        assert_eq!(first_dir_index.context, Some(Context::Applications));
         assert_eq!(first_dir_index.directory_type, DirectoryType::Scalable);
         assert_eq!(first_dir_index.max_size, 256);
         assert_eq!(first_dir_index.min_size, 1);